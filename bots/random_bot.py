@@ -0,0 +1,27 @@
+#!/usr/bin/env python3
+# Example bot for the external bot protocol.
+#
+# The game writes one JSON line per betting phase, for example:
+#   {"player": 2, "round": 3, "max_round": 6, "time_limit": 5.0,
+#    "me": {"health": 3, "bullets": 1, "dodges": 0},
+#    "opponent": {"health": 2, "bullets": 2, "dodges": 1},
+#    "last_round": {"me": "Attack", "opponent": "Dodge", "damage_taken": 0, "damage_dealt": 0}}
+#
+# and expects one JSON line back before time_limit seconds have passed:
+#   {"action": "Idle" | "Attack" | "Dodge" | "Buff"}
+#
+# Late, missing or malformed answers leave the player idle for the round.
+import json
+import random
+import sys
+
+for line in sys.stdin:
+    state = json.loads(line)
+    actions = ["Idle", "Buff"]
+
+    if state["me"]["bullets"] > 0:
+        actions.append("Attack")
+    if state["me"]["dodges"] > 0:
+        actions.append("Dodge")
+
+    print(json.dumps({"action": random.choice(actions)}), flush=True)
@@ -0,0 +1,50 @@
+// Random agents against the duel environment, as a starting point for training.
+//
+//     cargo run --release --example duel_env
+use hit_a_key_game::headless::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const N_ENVS: usize = 64;
+const N_STEPS: usize = 10_000;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut envs = VecDuelEnv::new(N_ENVS, 0);
+    let observations = envs.reset(0);
+
+    println!(
+        "{} environments, observations of {} values",
+        envs.len(),
+        observations[0].players[0].len()
+    );
+
+    let mut episodes = 0;
+    let mut returns = [0.; 2];
+
+    for _ in 0..N_STEPS {
+        let actions: Vec<(Moves, Moves)> = (0..envs.len())
+            .map(|_| {
+                (
+                    MOVES[rng.random_range(0..MOVES.len())],
+                    MOVES[rng.random_range(0..MOVES.len())],
+                )
+            })
+            .collect();
+
+        for step in envs.step(&actions) {
+            returns[0] += step.rewards[0];
+            returns[1] += step.rewards[1];
+
+            if step.done {
+                episodes += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} episodes, mean return p1 {:.3} p2 {:.3}",
+        episodes,
+        returns[0] / episodes as f32,
+        returns[1] / episodes as f32
+    );
+}
@@ -0,0 +1,532 @@
+// Monte Carlo balance simulator: many duels under given rules and strategies,
+// reported as CSV files for spreadsheets.
+//
+//     cargo run --release --bin balance -- --a Hard --b Turtle --games 100000 \
+//         --set restore_threshold=30 --sweep golden_bullet_damage=2,3,5 --out balance-report
+//
+// Strategies swap seats every duel, so seat bias and strategy strength are reported apart.
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use hit_a_key_game::headless::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+const DEFAULT_GAMES: u32 = 10_000;
+const DEFAULT_STRATEGY: &str = "Normal";
+const DEFAULT_OUT: &str = "balance-report";
+// Two-sided 95% confidence
+const Z: f64 = 1.96;
+
+const RULES: [&str; 9] = [
+    "health",
+    "bullets",
+    "dodges",
+    "luck",
+    "marksmanship",
+    "max_round",
+    "golden_bullet_damage",
+    "buff_dice_bonus",
+    "restore_threshold",
+];
+
+fn rule<'a>(rules: &'a mut RuleSet, name: &str) -> Option<&'a mut u8> {
+    Some(match name {
+        "health" => &mut rules.health,
+        "bullets" => &mut rules.bullets,
+        "dodges" => &mut rules.dodges,
+        "luck" => &mut rules.luck,
+        "marksmanship" => &mut rules.marksmanship,
+        "max_round" => &mut rules.max_round,
+        "golden_bullet_damage" => &mut rules.golden_bullet_damage,
+        "buff_dice_bonus" => &mut rules.buff_dice_bonus,
+        "restore_threshold" => &mut rules.restore_threshold,
+        _ => return None,
+    })
+}
+
+// Dice need two faces, buffs only make them bigger
+fn check_rules(rules: &RuleSet) -> Result<(), String> {
+    if rules.luck < 2 || rules.marksmanship < 2 {
+        return Err("luck and marksmanship need at least 2".into());
+    }
+
+    if rules.max_round == 0 || rules.health == 0 {
+        return Err("max_round and health need at least 1".into());
+    }
+
+    Ok(())
+}
+
+struct Options {
+    strategies: [String; 2],
+    games: u32,
+    seed: Option<u64>,
+    rules: RuleSet,
+    sweep: Option<(String, Vec<u8>)>,
+    out: PathBuf,
+}
+
+fn parse_assignment(value: Option<String>) -> (String, String) {
+    value
+        .as_deref()
+        .and_then(|value| value.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .unwrap_or_else(|| usage())
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        strategies: [DEFAULT_STRATEGY.to_string(), DEFAULT_STRATEGY.to_string()],
+        games: DEFAULT_GAMES,
+        seed: None,
+        rules: RuleSet::default(),
+        sweep: None,
+        out: PathBuf::from(DEFAULT_OUT),
+    };
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--a" => options.strategies[0] = args.next().unwrap_or_else(|| usage()),
+            "--b" => options.strategies[1] = args.next().unwrap_or_else(|| usage()),
+            "--games" => {
+                options.games = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--seed" => {
+                options.seed = Some(
+                    args.next()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--set" => {
+                let (name, value) = parse_assignment(args.next());
+                let value = value.parse().unwrap_or_else(|_| usage());
+
+                *rule(&mut options.rules, &name).unwrap_or_else(|| usage()) = value;
+            }
+            "--sweep" => {
+                let (name, values) = parse_assignment(args.next());
+                let values = values
+                    .split(',')
+                    .map(|value| value.trim().parse().unwrap_or_else(|_| usage()))
+                    .collect();
+
+                if rule(&mut RuleSet::default(), &name).is_none() {
+                    usage();
+                }
+
+                options.sweep = Some((name, values));
+            }
+            "--out" => options.out = PathBuf::from(args.next().unwrap_or_else(|| usage())),
+            _ => usage(),
+        }
+    }
+
+    options
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: balance [--a STRATEGY] [--b STRATEGY] [--games N] [--seed S]\n\
+         \x20              [--set RULE=VALUE]... [--sweep RULE=V1,V2,...] [--out DIR]\n\
+         Rules: {}",
+        RULES.join(", ")
+    );
+    process::exit(2);
+}
+
+// Wins, losses and ties of whoever did something
+#[derive(Default, Clone, Copy)]
+struct Tally {
+    count: u64,
+    wins: u64,
+    losses: u64,
+    ties: u64,
+    damage_dealt: u64,
+}
+
+impl Tally {
+    fn add(&mut self, outcome: Option<bool>, damage_dealt: u8) {
+        self.count += 1;
+        self.damage_dealt += damage_dealt as u64;
+
+        match outcome {
+            Some(true) => self.wins += 1,
+            Some(false) => self.losses += 1,
+            None => self.ties += 1,
+        }
+    }
+
+    fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.count.max(1) as f64
+    }
+
+    fn average_damage(&self) -> f64 {
+        self.damage_dealt as f64 / self.count.max(1) as f64
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    games: u64,
+    rounds: u64,
+    seat_wins: [u64; 2],
+    strategy_wins: [u64; 2],
+    ties: u64,
+    // Strategy index and move
+    actions: BTreeMap<(usize, usize), Tally>,
+    buffs: BTreeMap<usize, Tally>,
+    game_overs: BTreeMap<(String, String), u64>,
+    lengths: BTreeMap<u8, u64>,
+}
+
+// Wilson score interval
+fn interval(successes: u64, n: u64) -> (f64, f64) {
+    let n = n.max(1) as f64;
+    let p = successes as f64 / n;
+    let denominator = 1. + Z * Z / n;
+    let center = (p + Z * Z / (2. * n)) / denominator;
+    let margin = Z * (p * (1. - p) / n + Z * Z / (4. * n * n)).sqrt() / denominator;
+
+    ((center - margin).max(0.), (center + margin).min(1.))
+}
+
+fn simulate(
+    rules: RuleSet,
+    bots: &mut [Box<dyn DuelBot>; 2],
+    games: u32,
+    rng: &mut StdRng,
+) -> Report {
+    let mut report = Report::default();
+
+    for game in 0..games {
+        // Strategy index sitting in each seat
+        let seats = if game.is_multiple_of(2) {
+            [0, 1]
+        } else {
+            [1, 0]
+        };
+        let mut duel = Duel::new(rules);
+        let mut last_round: Option<RoundReport> = None;
+        let mut played = vec![];
+
+        for (player, &strategy) in (1..).zip(&seats) {
+            bots[strategy].reset(player);
+        }
+
+        while duel.result.is_none() {
+            let mut actions = [PlayerStates::Idle; 2];
+
+            for (index, &strategy) in seats.iter().enumerate() {
+                let player = index as u8 + 1;
+                let outcome = last_round.map(|round| round.outcome(player));
+
+                actions[index] = bots[strategy].decide(&duel.view(player), outcome, rng);
+            }
+
+            let round = duel.play_round(actions, rng);
+
+            for (index, &action) in actions.iter().enumerate() {
+                played.push((
+                    index,
+                    action,
+                    round.buffs[index],
+                    round.damage_taken[1 - index],
+                ));
+            }
+
+            last_round = Some(round);
+        }
+
+        let result = duel.result.unwrap_or(MatchResult::Tie);
+        let winner = result.winner();
+        let outcome = |index: usize| winner.map(|winner| winner == index as u8 + 1);
+
+        report.games += 1;
+        report.rounds += duel.round as u64;
+        *report.lengths.entry(duel.round).or_default() += 1;
+
+        match winner {
+            Some(winner) => {
+                let index = (winner - 1) as usize;
+
+                report.seat_wins[index] += 1;
+                report.strategy_wins[seats[index]] += 1;
+            }
+            None => report.ties += 1,
+        }
+
+        let result_name = match result {
+            MatchResult::Winner(_) => "Winner",
+            MatchResult::Tie => "Tie",
+        };
+        let cause = duel
+            .ending
+            .map_or("Unknown".to_string(), |cause| format!("{:?}", cause));
+        *report
+            .game_overs
+            .entry((result_name.to_string(), cause))
+            .or_default() += 1;
+
+        for (index, action, buff, damage_dealt) in played {
+            let action = MOVES
+                .iter()
+                .position(|other| *other == Moves::from(action))
+                .unwrap_or(0);
+
+            report
+                .actions
+                .entry((seats[index], action))
+                .or_default()
+                .add(outcome(index), damage_dealt);
+
+            if let Some(buff) = buff.and_then(|buff| BUFFES.iter().position(|other| *other == buff))
+            {
+                report
+                    .buffs
+                    .entry(buff)
+                    .or_default()
+                    .add(outcome(index), damage_dealt);
+            }
+        }
+    }
+
+    report
+}
+
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn create(out: &Path, name: &str, header: &str) -> io::Result<BufWriter<File>> {
+    let mut file = BufWriter::new(File::create(out.join(name))?);
+    writeln!(file, "variant,{}", header)?;
+
+    Ok(file)
+}
+
+fn write_reports(
+    out: &Path,
+    strategies: &[String; 2],
+    variants: &[(String, RuleSet, Report)],
+) -> io::Result<()> {
+    fs::create_dir_all(out)?;
+
+    let mut summary = create(
+        out,
+        "summary.csv",
+        &format!(
+            "{},games,avg_rounds,ties,seat1_wins,seat2_wins,seat1_win_rate,seat1_ci_low,seat1_ci_high,\
+             a_wins,b_wins,a_win_rate,a_ci_low,a_ci_high",
+            RULES.join(",")
+        ),
+    )?;
+    let mut actions = create(
+        out,
+        "actions.csv",
+        "strategy,action,played,in_wins,in_losses,in_ties,win_rate_when_played,avg_damage_dealt",
+    )?;
+    let mut buffs = create(
+        out,
+        "buffs.csv",
+        "buff,drawn,drawer_wins,drawer_losses,ties,drawer_win_rate,avg_damage_dealt",
+    )?;
+    let mut game_overs = create(out, "game_overs.csv", "result,cause,count,share")?;
+    let mut lengths = create(out, "lengths.csv", "rounds,count,share")?;
+
+    for (variant, rules, report) in variants {
+        let variant = field(variant);
+        let games = report.games.max(1) as f64;
+        let decided = report.seat_wins[0] + report.seat_wins[1];
+        let (seat_low, seat_high) = interval(report.seat_wins[0], decided);
+        let (a_low, a_high) = interval(report.strategy_wins[0], report.games);
+        let rule_values: Vec<String> = RULES
+            .iter()
+            .map(|name| {
+                let mut rules = *rules;
+                rule(&mut rules, name).map_or(0, |value| *value).to_string()
+            })
+            .collect();
+
+        writeln!(
+            summary,
+            "{},{},{},{:.3},{},{},{},{:.4},{:.4},{:.4},{},{},{:.4},{:.4},{:.4}",
+            variant,
+            rule_values.join(","),
+            report.games,
+            report.rounds as f64 / games,
+            report.ties,
+            report.seat_wins[0],
+            report.seat_wins[1],
+            report.seat_wins[0] as f64 / decided.max(1) as f64,
+            seat_low,
+            seat_high,
+            report.strategy_wins[0],
+            report.strategy_wins[1],
+            report.strategy_wins[0] as f64 / games,
+            a_low,
+            a_high,
+        )?;
+
+        for ((strategy, action), tally) in &report.actions {
+            writeln!(
+                actions,
+                "{},{},{:?},{},{},{},{},{:.4},{:.4}",
+                variant,
+                field(&strategies[*strategy]),
+                MOVES[*action],
+                tally.count,
+                tally.wins,
+                tally.losses,
+                tally.ties,
+                tally.win_rate(),
+                tally.average_damage(),
+            )?;
+        }
+
+        for (buff, tally) in &report.buffs {
+            writeln!(
+                buffs,
+                "{},{:?},{},{},{},{},{:.4},{:.4}",
+                variant,
+                BUFFES[*buff],
+                tally.count,
+                tally.wins,
+                tally.losses,
+                tally.ties,
+                tally.win_rate(),
+                tally.average_damage(),
+            )?;
+        }
+
+        for ((result, cause), count) in &report.game_overs {
+            writeln!(
+                game_overs,
+                "{},{},{},{},{:.4}",
+                variant,
+                result,
+                cause,
+                count,
+                *count as f64 / games
+            )?;
+        }
+
+        for (rounds, count) in &report.lengths {
+            writeln!(
+                lengths,
+                "{},{},{},{:.4}",
+                variant,
+                rounds,
+                count,
+                *count as f64 / games
+            )?;
+        }
+    }
+
+    for file in [
+        &mut summary,
+        &mut actions,
+        &mut buffs,
+        &mut game_overs,
+        &mut lengths,
+    ] {
+        file.flush()?;
+    }
+
+    Ok(())
+}
+
+fn main() {
+    log_to_stderr();
+
+    let options = parse_options();
+    let registered = registered_bots(true);
+    let find = |name: &str| {
+        registered
+            .iter()
+            .find(|bot| bot.name.eq_ignore_ascii_case(name))
+            .unwrap_or_else(|| {
+                let names: Vec<&str> = registered.iter().map(|bot| bot.name.as_str()).collect();
+                eprintln!(
+                    "Unknown strategy {}, pick one of: {}",
+                    name,
+                    names.join(", ")
+                );
+                process::exit(2);
+            })
+    };
+    let strategies = [find(&options.strategies[0]), find(&options.strategies[1])];
+    let mut bots = strategies.map(|strategy| {
+        strategy.spawn().unwrap_or_else(|error| {
+            eprintln!("Could not start {}: {}", strategy.name, error);
+            process::exit(1);
+        })
+    });
+
+    let variants: Vec<(String, RuleSet)> = match &options.sweep {
+        Some((name, values)) => values
+            .iter()
+            .map(|value| {
+                let mut rules = options.rules;
+                *rule(&mut rules, name).unwrap_or_else(|| usage()) = *value;
+                (format!("{}={}", name, value), rules)
+            })
+            .collect(),
+        None => vec![("base".to_string(), options.rules)],
+    };
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    let mut reports = vec![];
+
+    for (variant, rules) in variants {
+        if let Err(error) = check_rules(&rules) {
+            eprintln!("Invalid rules for {}: {}", variant, error);
+            process::exit(2);
+        }
+
+        let report = simulate(rules, &mut bots, options.games, &mut rng);
+
+        println!(
+            "{:<28} games {:>8}  avg rounds {:>5.2}  seat 1 wins {:>5.1}%  {} wins {:>5.1}%  ties {:>5.1}%",
+            variant,
+            report.games,
+            report.rounds as f64 / report.games.max(1) as f64,
+            100. * report.seat_wins[0] as f64 / (report.seat_wins[0] + report.seat_wins[1]).max(1) as f64,
+            strategies[0].name,
+            100. * report.strategy_wins[0] as f64 / report.games.max(1) as f64,
+            100. * report.ties as f64 / report.games.max(1) as f64,
+        );
+
+        reports.push((variant, rules, report));
+    }
+
+    let names = strategies.map(|strategy| strategy.name.clone());
+
+    if let Err(error) = write_reports(&options.out, &names, &reports) {
+        eprintln!(
+            "Could not write reports to {}: {}",
+            options.out.display(),
+            error
+        );
+        process::exit(1);
+    }
+
+    println!("CSV reports written to {}", options.out.display());
+}
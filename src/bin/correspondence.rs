@@ -0,0 +1,219 @@
+// Correspondence duels: one round per exchange of signed move files, by mail,
+// chat or a shared folder.
+//
+//     cargo run --bin correspondence -- key --out alice.key
+//     cargo run --bin correspondence -- new --players ALICE_PUB BOB_PUB --out duel.json
+//     cargo run --bin correspondence -- move --match duel.json --key alice.key --choice attack
+//     cargo run --bin correspondence -- resolve --match duel.json player1-round1.json player2-round1.json
+//     cargo run --bin correspondence -- show --match duel.json
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use hit_a_key_game::headless::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: correspondence key --out FILE\n\
+         \x20      correspondence new --players PUBLIC_KEY PUBLIC_KEY [--seed S] --out MATCH\n\
+         \x20      correspondence move --match MATCH --key FILE --choice idle|attack|dodge|buff [--out FILE]\n\
+         \x20      correspondence resolve --match MATCH MOVE MOVE [--out MATCH]\n\
+         \x20      correspondence show --match MATCH"
+    );
+    process::exit(2);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> T {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|error| fail(format!("Could not read {}: {}", path.display(), error)));
+
+    serde_json::from_str(&text)
+        .unwrap_or_else(|error| fail(format!("Could not parse {}: {}", path.display(), error)))
+}
+
+fn write_json(path: &Path, value: &impl Serialize) {
+    let text = serde_json::to_string_pretty(value).unwrap_or_else(|error| fail(error));
+
+    fs::write(path, text)
+        .unwrap_or_else(|error| fail(format!("Could not write {}: {}", path.display(), error)));
+}
+
+fn parse_choice(value: &str) -> Moves {
+    MOVES
+        .into_iter()
+        .find(|choice| format!("{:?}", choice).eq_ignore_ascii_case(value))
+        .unwrap_or_else(|| usage())
+}
+
+fn describe_round(entry: &CorrespondenceRound) -> String {
+    let mut line = format!("Round {}:", entry.round);
+
+    for player in 0..2 {
+        let buff = entry.buffs[player].map_or(String::new(), |buff| format!(" ({:?})", buff));
+
+        line += &format!(
+            " P{} {:?}{}, took {}, {} HP left.",
+            player + 1,
+            entry.states[player],
+            buff,
+            entry.damage_taken[player],
+            entry.health[player]
+        );
+    }
+
+    line
+}
+
+fn describe_match(match_file: &MatchFile) {
+    println!("Match {} (seed {})", match_file.id, match_file.seed);
+
+    for (index, player) in match_file.players.iter().enumerate() {
+        println!("Player {}: {}", index + 1, player);
+    }
+
+    for entry in &match_file.history {
+        println!("{}", describe_round(entry));
+    }
+
+    if let Err(error) = match_file.replay() {
+        println!("This match file can't be trusted: {}", error);
+        return;
+    }
+
+    if match_file.finished {
+        match match_file.winner {
+            Some(winner) => println!("Game over, player {} won", winner),
+            None => println!("Game over, it's a tie"),
+        }
+    } else {
+        println!("Waiting for the moves of round {}", match_file.next_round());
+    }
+}
+
+// Options after the command, positional arguments kept in order
+fn parse_options(mut args: impl Iterator<Item = String>) -> (Vec<(String, String)>, Vec<String>) {
+    let mut options = vec![];
+    let mut positional = vec![];
+
+    while let Some(arg) = args.next() {
+        if let Some(name) = arg.strip_prefix("--") {
+            let value = args.next().unwrap_or_else(|| usage());
+
+            // `--players` takes two values
+            if name == "players" {
+                options.push((name.to_string(), value));
+                options.push((name.to_string(), args.next().unwrap_or_else(|| usage())));
+            } else {
+                options.push((name.to_string(), value));
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    (options, positional)
+}
+
+fn option<'a>(options: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    options
+        .iter()
+        .find(|(option, _)| option == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn required<'a>(options: &'a [(String, String)], name: &str) -> &'a str {
+    option(options, name).unwrap_or_else(|| usage())
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let command = args.next().unwrap_or_else(|| usage());
+    let (options, positional) = parse_options(args);
+
+    match command.as_str() {
+        "key" => {
+            let key = generate_key();
+            let out = PathBuf::from(required(&options, "out"));
+
+            fs::write(&out, key_to_hex(&key)).unwrap_or_else(|error| {
+                fail(format!("Could not write {}: {}", out.display(), error))
+            });
+
+            println!("Secret key saved to {}, keep it to yourself", out.display());
+            println!("Public key: {}", public_key(&key));
+        }
+        "new" => {
+            let players: Vec<String> = options
+                .iter()
+                .filter(|(name, _)| name == "players")
+                .map(|(_, value)| value.trim().to_lowercase())
+                .collect();
+            let players: [String; 2] = players.try_into().unwrap_or_else(|_| usage());
+            let seed = option(&options, "seed")
+                .map(|seed| seed.parse().unwrap_or_else(|_| usage()))
+                .unwrap_or_else(rand::random);
+            let match_file = MatchFile::new(players, seed);
+
+            write_json(Path::new(required(&options, "out")), &match_file);
+            describe_match(&match_file);
+        }
+        "move" => {
+            let match_file: MatchFile = read_json(Path::new(required(&options, "match")));
+            let key_path = required(&options, "key");
+            let key = fs::read_to_string(key_path)
+                .map_err(|error| error.to_string())
+                .and_then(|hex| key_from_hex(&hex))
+                .unwrap_or_else(|error| fail(format!("Could not read {}: {}", key_path, error)));
+            let choice = parse_choice(required(&options, "choice"));
+            let move_file = match_file
+                .sign_move(&key, choice)
+                .unwrap_or_else(|error| fail(error));
+            let out = option(&options, "out").map_or_else(
+                || {
+                    PathBuf::from(format!(
+                        "player{}-round{}.json",
+                        move_file.player, move_file.round
+                    ))
+                },
+                PathBuf::from,
+            );
+
+            write_json(&out, &move_file);
+            println!(
+                "Signed {:?} for player {} in round {} into {}",
+                choice,
+                move_file.player,
+                move_file.round,
+                out.display()
+            );
+        }
+        "resolve" => {
+            let match_path = PathBuf::from(required(&options, "match"));
+            let mut match_file: MatchFile = read_json(&match_path);
+            let [first, second] = positional.as_slice() else {
+                usage();
+            };
+            let moves: [MoveFile; 2] = [read_json(Path::new(first)), read_json(Path::new(second))];
+            let entry = match_file
+                .resolve([&moves[0], &moves[1]])
+                .unwrap_or_else(|error| fail(error));
+
+            println!("{}", describe_round(entry));
+
+            let out = option(&options, "out").map_or(match_path, PathBuf::from);
+
+            write_json(&out, &match_file);
+            describe_match(&match_file);
+        }
+        "show" => describe_match(&read_json(Path::new(required(&options, "match")))),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,10 @@
+// Dedicated server for networked duels, without any window.
+//
+//     cargo run --bin server -- --address 0.0.0.0:7780 --betting-time 5 --log results.jsonl
+use bevy::prelude::*;
+
+use hit_a_key_game::ServerPlugin;
+
+fn main() {
+    App::new().add_plugins(ServerPlugin).run();
+}
@@ -0,0 +1,101 @@
+// Terminal viewer for a match streamed with `--spectators`.
+//
+//     cargo run --bin spectator -- 127.0.0.1:7779
+use std::{
+    env,
+    io::{BufRead, BufReader},
+    net::TcpStream,
+    process,
+};
+
+use hit_a_key_game::headless::*;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7779";
+
+fn describe_player(snapshot: &PlayerSnapshot) -> String {
+    let buff = snapshot
+        .buff
+        .map_or(String::new(), |buff| format!(" {:?}", buff));
+
+    format!(
+        "P{} {}/{} HP {} bullets {} dodges {:?}{}",
+        snapshot.player,
+        snapshot.health,
+        snapshot.max_health,
+        snapshot.bullets,
+        snapshot.dodges,
+        snapshot.state,
+        buff
+    )
+}
+
+fn describe(message: &SpectatorMessage) -> String {
+    match message {
+        SpectatorMessage::Snapshot(snapshot) => {
+            let mut line = format!(
+                "Round {}/{} {:?}",
+                snapshot.round, snapshot.max_round, snapshot.play_state
+            );
+
+            if snapshot.play_state == PlayStates::Betting {
+                line += &format!(" ({:.0}s)", snapshot.betting_time_left);
+            }
+
+            for player in &snapshot.players {
+                line += &format!(" | {}", describe_player(player));
+            }
+
+            if let Some(win_chances) = snapshot.win_chances {
+                line += &format!(
+                    " | P1 {:.0}% tie {:.0}% P2 {:.0}%",
+                    win_chances.players[0] * 100.,
+                    win_chances.tie * 100.,
+                    win_chances.players[1] * 100.
+                );
+            }
+
+            line
+        }
+        SpectatorMessage::PlayState { state } => format!("-- {:?} --", state),
+        SpectatorMessage::Attack {
+            player,
+            attacker,
+            damage,
+        } => format!("P{} shoots at P{} for {}", attacker, player, damage),
+        SpectatorMessage::Damage {
+            player,
+            attacker,
+            value,
+        } => format!("P{} takes {} from P{}", player, value, attacker),
+        SpectatorMessage::Missed { player } => format!("P{} is missed", player),
+        SpectatorMessage::Dodged { player } => format!("P{} dodges", player),
+        SpectatorMessage::GameOver { winner } => match winner {
+            Some(winner) => format!("Game over, player {} won", winner),
+            None => "Game over, it's a tie".to_string(),
+        },
+    }
+}
+
+fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let stream = TcpStream::connect(&address).unwrap_or_else(|error| {
+        eprintln!("Could not connect to {}: {}", address, error);
+        process::exit(1);
+    });
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        match serde_json::from_str::<SpectatorMessage>(&line) {
+            Ok(message) => println!("{}", describe(&message)),
+            Err(error) => eprintln!("Unreadable message ({}): {}", error, line),
+        }
+    }
+
+    println!("Stream closed");
+}
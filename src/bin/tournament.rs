@@ -0,0 +1,191 @@
+// Round-robin tournament between the registered bots, without any window.
+//
+//     cargo run --release --bin tournament -- --games 1000 --seed 42 --no-external
+use std::{env, process};
+
+use hit_a_key_game::headless::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+const DEFAULT_GAMES: u32 = 1000;
+// Two-sided 95% confidence
+const Z: f64 = 1.96;
+
+struct Options {
+    games: u32,
+    seed: Option<u64>,
+    include_external: bool,
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        games: DEFAULT_GAMES,
+        seed: None,
+        include_external: true,
+    };
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => {
+                options.games = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--seed" => {
+                options.seed = Some(
+                    args.next()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--no-external" => options.include_external = false,
+            _ => usage(),
+        }
+    }
+
+    options
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: tournament [--games N] [--seed S] [--no-external]");
+    process::exit(2);
+}
+
+#[derive(Default, Clone, Copy)]
+struct Standing {
+    wins: u32,
+    ties: u32,
+    losses: u32,
+    rounds: u64,
+}
+
+impl Standing {
+    fn games(&self) -> u32 {
+        self.wins + self.ties + self.losses
+    }
+
+    fn record(&mut self, record: &DuelRecord, player: u8) {
+        match record.result.winner() {
+            Some(winner) if winner == player => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => self.ties += 1,
+        }
+
+        self.rounds += record.rounds as u64;
+    }
+
+    fn average_rounds(&self) -> f64 {
+        self.rounds as f64 / self.games().max(1) as f64
+    }
+
+    // Wilson score interval of the win rate
+    fn win_rate_interval(&self) -> (f64, f64, f64) {
+        let n = self.games().max(1) as f64;
+        let p = self.wins as f64 / n;
+        let denominator = 1. + Z * Z / n;
+        let center = (p + Z * Z / (2. * n)) / denominator;
+        let margin = Z * (p * (1. - p) / n + Z * Z / (4. * n * n)).sqrt() / denominator;
+
+        (p, (center - margin).max(0.), (center + margin).min(1.))
+    }
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.1}%", value * 100.)
+}
+
+fn main() {
+    log_to_stderr();
+
+    let options = parse_options();
+    let bots = registered_bots(options.include_external);
+
+    if bots.len() < 2 {
+        eprintln!("Need at least two registered bots");
+        process::exit(1);
+    }
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    let mut standings = vec![Standing::default(); bots.len()];
+    let mut pairings = vec![];
+
+    for a in 0..bots.len() {
+        for b in (a + 1)..bots.len() {
+            let (mut bot_a, mut bot_b) = match (bots[a].spawn(), bots[b].spawn()) {
+                (Ok(bot_a), Ok(bot_b)) => (bot_a, bot_b),
+                (Err(error), _) | (_, Err(error)) => {
+                    eprintln!("Skipping {} vs {}: {}", bots[a].name, bots[b].name, error);
+                    continue;
+                }
+            };
+            let mut pairing = Standing::default();
+
+            // Seats alternate so neither bot keeps the player one side
+            for game in 0..options.games {
+                let (record, seat_a) = if game.is_multiple_of(2) {
+                    (play_duel([bot_a.as_mut(), bot_b.as_mut()], &mut rng), 1)
+                } else {
+                    (play_duel([bot_b.as_mut(), bot_a.as_mut()], &mut rng), 2)
+                };
+
+                pairing.record(&record, seat_a);
+                standings[a].record(&record, seat_a);
+                standings[b].record(&record, 3 - seat_a);
+            }
+
+            pairings.push((a, b, pairing));
+        }
+    }
+
+    println!(
+        "{:<16} {:<16} {:>7} {:>7} {:>7} {:>11} {:>24}",
+        "Bot A", "Bot B", "A wins", "Ties", "B wins", "Avg rounds", "A win rate (95% CI)"
+    );
+
+    for (a, b, pairing) in &pairings {
+        let (rate, low, high) = pairing.win_rate_interval();
+
+        println!(
+            "{:<16} {:<16} {:>7} {:>7} {:>7} {:>11.2} {:>24}",
+            bots[*a].name,
+            bots[*b].name,
+            pairing.wins,
+            pairing.ties,
+            pairing.losses,
+            pairing.average_rounds(),
+            format!("{} [{}, {}]", percent(rate), percent(low), percent(high)),
+        );
+    }
+
+    println!();
+    println!(
+        "{:<16} {:>7} {:>7} {:>7} {:>7} {:>11} {:>24}",
+        "Bot", "Games", "Wins", "Ties", "Losses", "Avg rounds", "Win rate (95% CI)"
+    );
+
+    let mut order: Vec<usize> = (0..bots.len()).collect();
+    order.sort_by(|a, b| {
+        let rate = |index: usize| standings[index].win_rate_interval().0;
+        rate(*b).total_cmp(&rate(*a))
+    });
+
+    for index in order {
+        let standing = standings[index];
+        let (rate, low, high) = standing.win_rate_interval();
+
+        println!(
+            "{:<16} {:>7} {:>7} {:>7} {:>7} {:>11.2} {:>24}",
+            bots[index].name,
+            standing.games(),
+            standing.wins,
+            standing.ties,
+            standing.losses,
+            standing.average_rounds(),
+            format!("{} [{}, {}]", percent(rate), percent(low), percent(high)),
+        );
+    }
+}
@@ -1,38 +1,110 @@
 use bevy::prelude::*;
 use rand::{distr::StandardUniform, prelude::*};
+use serde::{Deserialize, Serialize};
 
+mod ai;
+mod audience;
 mod components;
+mod correspondence;
+mod dedicated_server;
+mod duel_bots;
+mod duel_env;
 mod events;
+mod external_bots;
+mod lobby;
+mod netplay;
+mod opponent_model;
+mod personalities;
+mod replays;
 mod resources;
+mod rules;
+mod scenarios;
 mod settings;
+mod spectators;
 mod states;
 mod systems;
+mod timeline;
 mod ui_components;
 mod ui_defaults;
 
+use ai::*;
+use audience::*;
 use components::*;
+use correspondence::*;
+use dedicated_server::*;
 use events::*;
+use external_bots::*;
+use lobby::*;
+use netplay::*;
+use opponent_model::*;
+use personalities::*;
+use replays::*;
 use resources::*;
+use rules::*;
+use scenarios::*;
 use settings::*;
+use spectators::*;
 use states::*;
+use timeline::*;
 use ui_components::*;
 use ui_defaults::*;
 
+// Window-free access to the duel rules for the extra binaries
+pub mod headless {
+    pub use super::ai::{Difficulties, DuelView, FighterView};
+    pub use super::components::{Buffes, PlayerStates, BUFFES};
+    pub use super::correspondence::{
+        generate_key, key_from_hex, key_to_hex, public_key, CorrespondenceRound, MatchFile,
+        MoveFile,
+    };
+    pub use super::duel_bots::{
+        log_to_stderr, play_duel, registered_bots, BotKind, DuelBot, DuelRecord, RegisteredBot,
+    };
+    pub use super::duel_env::{
+        DuelEnv, Observation, StepInfo, StepResult, VecDuelEnv, OBSERVATION_SIZE,
+    };
+    pub use super::opponent_model::{Moves, MOVES};
+    pub use super::rules::{Duel, GameOverCauses, MatchResult, RoundReport, RuleSet, WinChances};
+    pub use super::settings::N_MAX_ROUND;
+    pub use super::spectators::{MatchSnapshot, PlayerSnapshot, SpectatorMessage};
+    pub use super::states::PlayStates;
+}
+
 // Game
 // ================================================================
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((events::plugin, resources::plugin, states::plugin));
+    app.add_plugins((
+        events::plugin,
+        resources::plugin,
+        scenarios::plugin,
+        personalities::plugin,
+        external_bots::plugin,
+        netplay::plugin,
+        spectators::plugin,
+        correspondence::plugin,
+        dedicated_server::client_plugin,
+        audience::plugin,
+        states::plugin,
+    ));
 
     app.add_plugins((
         systems::plugin,
         systems::loading::plugin,
         systems::menu::plugin,
+        systems::attract_mode::plugin,
+        systems::online::plugin,
+        systems::lobby::plugin,
+        systems::spectating::plugin,
+        systems::watching::plugin,
+        systems::resolving::plugin,
+        systems::joining::plugin,
         systems::in_game::plugin,
     ));
 
     app.add_plugins((
         // Chronological order
+        systems::in_game::drafting::plugin,
         systems::in_game::preparing::plugin,
         systems::in_game::countdown::plugin,
         systems::in_game::betting::plugin,
@@ -40,4 +112,27 @@ pub(super) fn plugin(app: &mut App) {
         systems::in_game::rounding_up::plugin,
         systems::in_game::game_over::plugin,
     ));
+
+    app.add_plugins((
+        // Match modes
+        systems::in_game::quick_draw::plugin,
+        systems::in_game::hidden_choices::plugin,
+        systems::in_game::scenario::plugin,
+        systems::in_game::ai_players::plugin,
+        systems::in_game::adaptive_difficulty::plugin,
+        systems::in_game::external_players::plugin,
+        systems::in_game::match_history::plugin,
+        systems::in_game::win_meter::plugin,
+        systems::in_game::audience_voting::plugin,
+        systems::in_game::replay_recording::plugin,
+        systems::in_game::replay_viewer::plugin,
+        systems::in_game::timeline::plugin,
+    ));
+}
+
+// Dedicated server
+// ================================================================
+
+pub(super) fn server_plugin(app: &mut App) {
+    app.add_plugins(dedicated_server::plugin);
 }
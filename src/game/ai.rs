@@ -0,0 +1,213 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulties {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulties {
+    pub fn level(&self) -> f32 {
+        match self {
+            Difficulties::Easy => 0.,
+            Difficulties::Normal => 1.,
+            Difficulties::Hard => 2.,
+        }
+    }
+
+    // Fractional levels pick between the two closest difficulties
+    pub fn from_level(level: f32, rng: &mut impl Rng) -> Self {
+        let level = level.clamp(0., 2.);
+        let lower = level.floor();
+        let rounded = if rng.random_bool((level - lower) as f64) {
+            lower + 1.
+        } else {
+            lower
+        };
+
+        match rounded as u8 {
+            0 => Difficulties::Easy,
+            1 => Difficulties::Normal,
+            _ => Difficulties::Hard,
+        }
+    }
+
+    // Seconds after the start of betting before the bot commits to its choice
+    pub fn reaction_range(&self) -> std::ops::Range<f32> {
+        match self {
+            Difficulties::Easy => 1.5..4.0,
+            Difficulties::Normal => 0.8..3.0,
+            Difficulties::Hard => 0.3..2.0,
+        }
+    }
+
+    // Seconds after the "DRAW!" signal before the bot pulls the trigger
+    pub fn draw_range(&self) -> std::ops::Range<f32> {
+        match self {
+            Difficulties::Easy => 0.4..0.7,
+            Difficulties::Normal => 0.25..0.45,
+            Difficulties::Hard => 0.15..0.3,
+        }
+    }
+}
+
+// Linear blend of the ranges of the two closest difficulties
+pub fn blend_range(
+    level: f32,
+    range: fn(&Difficulties) -> std::ops::Range<f32>,
+) -> std::ops::Range<f32> {
+    let level = level.clamp(0., 2.);
+    let (lower, upper, t) = if level <= 1. {
+        (Difficulties::Easy, Difficulties::Normal, level)
+    } else {
+        (Difficulties::Normal, Difficulties::Hard, level - 1.)
+    };
+    let (lower, upper) = (range(&lower), range(&upper));
+
+    (lower.start + (upper.start - lower.start) * t)..(lower.end + (upper.end - lower.end) * t)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FighterView {
+    pub health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuelView {
+    pub round: u8,
+    pub me: FighterView,
+    pub opponent: FighterView,
+}
+
+impl DuelView {
+    pub fn is_last_round(&self) -> bool {
+        self.round >= N_MAX_ROUND
+    }
+
+    // The same duel seen from the other side of the table
+    pub fn flipped(&self) -> DuelView {
+        DuelView {
+            round: self.round,
+            me: self.opponent,
+            opponent: self.me,
+        }
+    }
+
+    pub fn available_actions(&self) -> Vec<PlayerStates> {
+        let mut actions = vec![PlayerStates::Idle, PlayerStates::Buffing];
+
+        if self.me.bullets > 0 {
+            actions.push(PlayerStates::Attacking);
+        }
+
+        if self.me.dodges > 0 {
+            actions.push(PlayerStates::Dodging);
+        }
+
+        actions
+    }
+}
+
+pub fn decide(difficulty: Difficulties, view: &DuelView, rng: &mut impl Rng) -> PlayerStates {
+    match difficulty {
+        Difficulties::Easy => easy(view, rng),
+        Difficulties::Normal => normal(view, rng),
+        Difficulties::Hard => hard(view, rng),
+    }
+}
+
+fn pick(actions: &[PlayerStates], rng: &mut impl Rng) -> PlayerStates {
+    actions[rng.random_range(0..actions.len())]
+}
+
+// Anything goes, as long as it is possible
+fn easy(view: &DuelView, rng: &mut impl Rng) -> PlayerStates {
+    pick(&view.available_actions(), rng)
+}
+
+// Reacts to the obvious threats and openings, random otherwise
+fn normal(view: &DuelView, rng: &mut impl Rng) -> PlayerStates {
+    let can_shoot = view.me.bullets > 0;
+    let can_dodge = view.me.dodges > 0;
+    let threatened = view.opponent.bullets > 0;
+
+    if !threatened {
+        return if can_shoot {
+            PlayerStates::Attacking
+        } else {
+            PlayerStates::Buffing
+        };
+    }
+
+    if can_dodge && view.me.health <= DEFAULT_DAMAGE && rng.random_bool(0.7) {
+        return PlayerStates::Dodging;
+    }
+
+    if can_shoot && view.opponent.dodges == 0 && rng.random_bool(0.6) {
+        return PlayerStates::Attacking;
+    }
+
+    easy(view, rng)
+}
+
+// Plays around lethal shots, round limits and the out of ammo rule
+fn hard(view: &DuelView, rng: &mut impl Rng) -> PlayerStates {
+    let can_shoot = view.me.bullets > 0;
+    let can_dodge = view.me.dodges > 0;
+    let threatened = view.opponent.bullets > 0;
+    let lethal = view.opponent.health <= DEFAULT_DAMAGE;
+    let in_danger = threatened && view.me.health <= DEFAULT_DAMAGE;
+
+    if view.is_last_round() {
+        return match view.me.health.cmp(&view.opponent.health) {
+            // Ahead on health: only losing health can lose the duel
+            std::cmp::Ordering::Greater if threatened && can_dodge => PlayerStates::Dodging,
+            std::cmp::Ordering::Greater => PlayerStates::Buffing,
+            _ if can_shoot => PlayerStates::Attacking,
+            _ => PlayerStates::Buffing,
+        };
+    }
+
+    if in_danger && can_dodge && !(lethal && can_shoot && view.opponent.dodges == 0) {
+        return PlayerStates::Dodging;
+    }
+
+    if can_shoot && lethal && view.opponent.dodges == 0 {
+        return PlayerStates::Attacking;
+    }
+
+    if !threatened {
+        // Keep the last bullet while behind, the out of ammo rule would end the duel
+        if can_shoot && (view.me.bullets > 1 || view.me.health >= view.opponent.health) {
+            return PlayerStates::Attacking;
+        }
+
+        return PlayerStates::Buffing;
+    }
+
+    // Opponent still has a dodge: bait it out before spending bullets
+    if view.opponent.dodges > 0 && rng.random_bool(0.5) {
+        return if can_dodge && rng.random_bool(0.5) {
+            PlayerStates::Dodging
+        } else {
+            PlayerStates::Buffing
+        };
+    }
+
+    if can_shoot && rng.random_bool(0.7) {
+        return PlayerStates::Attacking;
+    }
+
+    normal(view, rng)
+}
+
+pub fn draft(view: &DuelView, rng: &mut impl Rng) -> DraftStats {
+    if view.me.health <= view.opponent.health && rng.random_bool(0.5) {
+        return DraftStats::Health;
+    }
+
+    DRAFT_STATS[rng.random_range(0..DRAFT_STATS.len())]
+}
@@ -0,0 +1,252 @@
+// Audience votes on the modifier of the next round, through a tiny HTTP
+// server meant for a local network or a stream overlay:
+//
+//     hit_a_key_game --audience 0.0.0.0:8080
+//
+// GET / shows the open poll with one button per option, POST /vote/<index>
+// casts a vote (one per address and poll) and GET /poll returns it as JSON.
+use std::{
+    collections::HashSet,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RoundModifiers {
+    DoubleDamage,
+    NoDodges,
+    FreeBuff,
+}
+
+pub const ROUND_MODIFIERS: [RoundModifiers; 3] = [
+    RoundModifiers::DoubleDamage,
+    RoundModifiers::NoDodges,
+    RoundModifiers::FreeBuff,
+];
+
+impl RoundModifiers {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RoundModifiers::DoubleDamage => "Double damage",
+            RoundModifiers::NoDodges => "No dodges",
+            RoundModifiers::FreeBuff => "Free buff",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Poll {
+    pub open: bool,
+    pub options: Vec<RoundModifiers>,
+    pub votes: Vec<u32>,
+    #[serde(skip)]
+    voters: HashSet<IpAddr>,
+}
+
+impl Poll {
+    fn vote(&mut self, option: usize, voter: IpAddr) -> bool {
+        if !self.open || option >= self.votes.len() || !self.voters.insert(voter) {
+            return false;
+        }
+
+        self.votes[option] += 1;
+        true
+    }
+
+    fn page(&self) -> String {
+        let body = if self.open {
+            self.options
+                .iter()
+                .zip(&self.votes)
+                .enumerate()
+                .map(|(index, (option, votes))| {
+                    format!(
+                        "<form method=\"post\" action=\"/vote/{}\"><button>{}</button> {} votes</form>",
+                        index,
+                        option.label(),
+                        votes
+                    )
+                })
+                .collect::<String>()
+        } else {
+            "<p>No vote right now, the next one opens between rounds.</p>".to_string()
+        };
+
+        format!(
+            "<!doctype html><html><head><meta charset=\"utf-8\"><meta http-equiv=\"refresh\" content=\"2\"><title>Next round</title></head><body><h1>Next round</h1>{}</body></html>",
+            body
+        )
+    }
+}
+
+#[derive(Resource)]
+pub struct AudienceServer {
+    pub address: SocketAddr,
+    poll: Arc<Mutex<Poll>>,
+}
+
+impl AudienceServer {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let poll: Arc<Mutex<Poll>> = Arc::default();
+        let shared = poll.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let poll = shared.clone();
+
+                thread::spawn(move || {
+                    if let Err(error) = serve(stream, &poll) {
+                        warn!("Audience request failed: {}", error);
+                    }
+                });
+            }
+        });
+
+        Ok(AudienceServer { address, poll })
+    }
+
+    // `--audience ADDRESS` on the command line
+    pub fn from_args() -> Option<io::Result<Self>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--audience" {
+                return Some(match args.next() {
+                    Some(address) => AudienceServer::bind(&address),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--audience needs an address",
+                    )),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn poll(&self) -> Poll {
+        self.poll
+            .lock()
+            .map(|poll| poll.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn open(&self, options: Vec<RoundModifiers>) {
+        if let Ok(mut poll) = self.poll.lock() {
+            *poll = Poll {
+                open: true,
+                votes: vec![0; options.len()],
+                options,
+                voters: HashSet::new(),
+            };
+        }
+    }
+
+    // Most voted option, ties broken at random, nothing without votes
+    pub fn close(&self, rng: &mut impl Rng) -> Option<RoundModifiers> {
+        let mut poll = self.poll.lock().ok()?;
+        poll.open = false;
+
+        let most = poll.votes.iter().copied().max().filter(|&most| most > 0)?;
+        let winners: Vec<_> = poll
+            .options
+            .iter()
+            .zip(&poll.votes)
+            .filter(|(_, &votes)| votes == most)
+            .map(|(&option, _)| option)
+            .collect();
+
+        winners.choose(rng).copied()
+    }
+}
+
+// Refuses lines longer than AUDIENCE_MAX_LINE instead of buffering them
+fn read_line_capped(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let length = reader.take(AUDIENCE_MAX_LINE as u64).read_line(line)?;
+
+    if length == AUDIENCE_MAX_LINE && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+
+    Ok(length)
+}
+
+fn serve(stream: TcpStream, poll: &Mutex<Poll>) -> io::Result<()> {
+    // A client that stalls only holds its own thread for a while
+    let timeout = Some(Duration::from_secs_f32(AUDIENCE_TIMEOUT));
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+
+    let voter = stream.peer_addr()?.ip();
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    read_line_capped(&mut reader, &mut request_line)?;
+
+    // Headers are not needed, only skipped
+    let mut header = String::new();
+    for _ in 0..AUDIENCE_MAX_HEADERS {
+        if read_line_capped(&mut reader, &mut header)? <= 2 {
+            break;
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let mut poll = poll
+        .lock()
+        .map_err(|_| io::Error::other("poll lock poisoned"))?;
+
+    let (status, content_type, body, location) = match (method, path) {
+        ("GET", "/") => ("200 OK", "text/html", poll.page(), None),
+        ("GET", "/poll") => (
+            "200 OK",
+            "application/json",
+            serde_json::to_string(&*poll).map_err(io::Error::other)?,
+            None,
+        ),
+        ("POST", path) if path.starts_with("/vote/") => {
+            let option = path["/vote/".len()..].parse().unwrap_or(usize::MAX);
+            poll.vote(option, voter);
+
+            ("303 See Other", "text/plain", String::new(), Some("/"))
+        }
+        _ => ("404 Not Found", "text/plain", "Not found".to_string(), None),
+    };
+
+    drop(poll);
+
+    let location = location.map_or(String::new(), |location| {
+        format!("Location: {}\r\n", location)
+    });
+    let mut stream = reader.into_inner();
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        location,
+        body
+    )
+}
+
+pub(super) fn plugin(app: &mut App) {
+    match AudienceServer::from_args() {
+        Some(Ok(server)) => {
+            info!("Audience votes open on http://{}", server.address);
+            app.insert_resource(server);
+        }
+        Some(Err(error)) => error!("Could not start audience server: {}", error),
+        None => {}
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::AddAssign;
+use std::{ops::AddAssign, time::Duration};
 
 use super::*;
 
@@ -11,6 +11,9 @@ pub struct MenuEntity;
 #[derive(Component)]
 pub struct LoadingEntity;
 
+#[derive(Component)]
+pub struct LobbyEntity;
+
 #[derive(Component)]
 #[require(
     Health,
@@ -20,7 +23,10 @@ pub struct LoadingEntity;
     Dodges,
     Bullets,
     Marksmanship,
-    HandTextureIndices
+    HandTextureIndices,
+    DraftPicks,
+    QuickDraw,
+    Handicap
 )]
 pub struct Player {
     pub value: u8,
@@ -34,12 +40,14 @@ pub struct Target {
 #[derive(Component)]
 pub struct Health {
     pub value: u8,
+    pub max: u8,
 }
 
 impl Default for Health {
     fn default() -> Self {
         Health {
             value: DEFAULT_HEALTH,
+            max: DEFAULT_HEALTH,
         }
     }
 }
@@ -50,22 +58,22 @@ pub struct Dice {
 }
 
 impl Dice {
-    pub fn roll(&self) -> u8 {
-        let mut rng = rand::rng();
-
+    pub fn roll(&self, rng: &mut impl Rng) -> u8 {
         rng.random_range(1..self.value)
     }
 }
 
+// Stacked buffs stop at the biggest die rather than wrapping around
 impl AddAssign for Dice {
     fn add_assign(&mut self, other: Self) {
-        self.value += other.value;
+        self.value = self.value.saturating_add(other.value);
     }
 }
 
 #[derive(Component)]
 pub struct Luck {
     pub value: Dice,
+    pub base: Dice,
 }
 
 impl Default for Luck {
@@ -74,6 +82,9 @@ impl Default for Luck {
             value: Dice {
                 value: DEFAULT_LUCK,
             },
+            base: Dice {
+                value: DEFAULT_LUCK,
+            },
         }
     }
 }
@@ -81,6 +92,7 @@ impl Default for Luck {
 #[derive(Component)]
 pub struct Marksmanship {
     pub value: Dice,
+    pub base: Dice,
 }
 
 impl Default for Marksmanship {
@@ -89,6 +101,9 @@ impl Default for Marksmanship {
             value: Dice {
                 value: DEFAULT_MARKSMANSHIP,
             },
+            base: Dice {
+                value: DEFAULT_MARKSMANSHIP,
+            },
         }
     }
 }
@@ -128,7 +143,7 @@ impl Default for Damage {
     }
 }
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Buffes {
     GoldenBulletBuff,
     IncreaseDamageBuff,
@@ -138,6 +153,15 @@ pub enum Buffes {
     MarksmanshipBuff,
 }
 
+pub const BUFFES: [Buffes; 6] = [
+    Buffes::GoldenBulletBuff,
+    Buffes::IncreaseDamageBuff,
+    Buffes::HealBuff,
+    Buffes::SuperHealBuff,
+    Buffes::LuckBuff,
+    Buffes::MarksmanshipBuff,
+];
+
 impl Distribution<Buffes> for StandardUniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Buffes {
         let index = rng.random_range(0..=5);
@@ -186,7 +210,80 @@ impl Default for HandTextureIndices {
 #[derive(Component)]
 pub struct DeletableAudio;
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DraftStats {
+    Health,
+    Bullets,
+    Dodges,
+    Luck,
+    Marksmanship,
+}
+
+pub const DRAFT_STATS: [DraftStats; 5] = [
+    DraftStats::Health,
+    DraftStats::Bullets,
+    DraftStats::Dodges,
+    DraftStats::Luck,
+    DraftStats::Marksmanship,
+];
+
+#[derive(Component, Default)]
+pub struct DraftPicks {
+    pub allocation: [u8; DRAFT_STATS.len()],
+    pub cursor: usize,
+}
+
+impl DraftPicks {
+    pub fn spent(&self) -> u8 {
+        self.allocation.iter().sum()
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Handicap {
+    pub health: u8,
+    pub bullets: u8,
+    pub luck: u8,
+    pub betting_time: f32,
+}
+
+impl Handicap {
+    pub fn is_active(&self) -> bool {
+        *self != Handicap::default()
+    }
+
+    pub fn health(&self) -> Health {
+        Health {
+            value: DEFAULT_HEALTH + self.health,
+            max: DEFAULT_HEALTH + self.health,
+        }
+    }
+
+    pub fn bullets(&self) -> Bullets {
+        Bullets {
+            value: N_BULLETS + self.bullets,
+        }
+    }
+
+    pub fn luck(&self) -> Luck {
+        let dice = Dice {
+            value: DEFAULT_LUCK + self.luck,
+        };
+
+        Luck {
+            value: dice,
+            base: dice,
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct QuickDraw {
+    pub reaction: Option<Duration>,
+    pub false_start: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum PlayerStates {
     #[default]
     Idle,
@@ -201,6 +298,10 @@ pub enum PlayerStates {
 pub struct PlayerState(pub PlayerStates);
 
 impl PlayerState {
+    pub fn request(&mut self, requested_state: PlayerStates, bullets: &Bullets, dodges: &Dodges) {
+        self.0 = rules::requested_state(requested_state, bullets.value, dodges.value);
+    }
+
     pub fn derive_hand_texture_index(&self, hand_texture_indices: &HandTextureIndices) -> usize {
         match self.0 {
             PlayerStates::Idle => hand_texture_indices.idle,
@@ -211,6 +312,95 @@ impl PlayerState {
     }
 }
 
+#[derive(Component)]
+pub struct ScriptedActions {
+    pub first_round: u8,
+    pub actions: Vec<PlayerStates>,
+}
+
+impl ScriptedActions {
+    pub fn action(&self, round: u8) -> PlayerStates {
+        round
+            .checked_sub(self.first_round)
+            .and_then(|index| self.actions.get(index as usize).copied())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Component)]
+pub struct AiController {
+    pub difficulty: Difficulties,
+    pub learning: bool,
+    pub adaptive: bool,
+    pub personality: Option<Handle<Personality>>,
+    pub opponent_previous: Option<Moves>,
+    pub decision: Option<PlayerStates>,
+    pub reaction: Timer,
+    pub draw_reaction: Duration,
+}
+
+impl AiController {
+    pub fn new(difficulty: Difficulties) -> Self {
+        AiController {
+            difficulty,
+            learning: false,
+            adaptive: false,
+            personality: None,
+            opponent_previous: None,
+            decision: None,
+            reaction: Timer::default(),
+            draw_reaction: Duration::ZERO,
+        }
+    }
+
+    // Falls back to the hard heuristics until it knows its opponent well enough
+    pub fn learner() -> Self {
+        AiController {
+            learning: true,
+            ..AiController::new(Difficulties::Hard)
+        }
+    }
+
+    pub fn with_personality(personality: Handle<Personality>) -> Self {
+        AiController {
+            personality: Some(personality),
+            ..AiController::new(Difficulties::Normal)
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ExternalBot {
+    pub config: Handle<ExternalBotConfig>,
+    pub process: Option<BotProcess>,
+    pub answered: bool,
+    pub decision: Option<PlayerStates>,
+    // Own and opponent health when the round started
+    pub health_at_betting: [u8; 2],
+    pub last_round: Option<RoundOutcome>,
+}
+
+impl ExternalBot {
+    pub fn new(config: Handle<ExternalBotConfig>) -> Self {
+        ExternalBot {
+            config,
+            process: None,
+            answered: false,
+            decision: None,
+            health_at_betting: [0; 2],
+            last_round: None,
+        }
+    }
+}
+
+// Played from the other machine in online duels
+#[derive(Component)]
+pub struct RemotePlayer;
+
+// Plays the choices of a recorded match
+#[derive(Component)]
+pub struct ReplayedPlayer;
+
 #[derive(Component)]
 pub struct KeyAssignment(pub [KeyCode; N_KEYS_PER_PLAYER]);
 
@@ -0,0 +1,483 @@
+// Correspondence duels, played one round at a time through files.
+// A match file holds the seed, both players' public keys and the history.
+// Each round both players sign a move file, and whoever holds both resolves
+// the round. The history keeps the signatures and every replay checks them
+// again, so nobody needs to trust the resolver's copy. A move file shows its
+// choice, so both should be sent at the same time or to a referee.
+//
+// The game resolves a round too, and shows it:
+//
+//     hit_a_key_game --correspondence duel.json player1-round1.json player2-round1.json
+use std::{fs, path::PathBuf};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::StdRng;
+use serde::de::DeserializeOwned;
+
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrespondenceRound {
+    pub round: u8,
+    pub choices: [Moves; 2],
+    // From the move files, hex encoded
+    pub signatures: [String; 2],
+    pub states: [PlayerStates; 2],
+    pub buffs: [Option<Buffes>; 2],
+    pub damage_taken: [u8; 2],
+    pub health: [u8; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchFile {
+    // Hash of the nonce, the seed and the players, which signatures cover
+    pub id: String,
+    pub nonce: u64,
+    pub seed: u64,
+    // Hex encoded ed25519 public keys of players 1 and 2
+    pub players: [String; 2],
+    pub history: Vec<CorrespondenceRound>,
+    pub winner: Option<u8>,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveFile {
+    pub match_id: String,
+    pub round: u8,
+    pub player: u8,
+    pub choice: Moves,
+    pub signature: String,
+}
+
+pub fn generate_key() -> SigningKey {
+    SigningKey::from_bytes(&rand::random())
+}
+
+pub fn public_key(key: &SigningKey) -> String {
+    to_hex(key.verifying_key().as_bytes())
+}
+
+pub fn key_to_hex(key: &SigningKey) -> String {
+    to_hex(&key.to_bytes())
+}
+
+pub fn key_from_hex(hex: &str) -> Result<SigningKey, String> {
+    let bytes: [u8; 32] = from_hex(hex.trim())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("not a hex encoded 32 byte key")?;
+
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+impl MatchFile {
+    pub fn new(players: [String; 2], seed: u64) -> Self {
+        let nonce = rand::random();
+
+        MatchFile {
+            id: match_id(nonce, seed, &players),
+            nonce,
+            seed,
+            players,
+            history: vec![],
+            winner: None,
+            finished: false,
+        }
+    }
+
+    pub fn next_round(&self) -> u8 {
+        self.history.len() as u8 + 1
+    }
+
+    pub fn player_of(&self, key: &SigningKey) -> Option<u8> {
+        let public_key = public_key(key);
+
+        self.players
+            .iter()
+            .position(|player| *player == public_key)
+            .map(|index| index as u8 + 1)
+    }
+
+    pub fn sign_move(&self, key: &SigningKey, choice: Moves) -> Result<MoveFile, String> {
+        if self.finished {
+            return Err("the match is over".into());
+        }
+
+        let player = self
+            .player_of(key)
+            .ok_or("this key doesn't play in the match")?;
+        let round = self.next_round();
+        let signature = key.sign(move_payload(&self.id, round, player, choice).as_bytes());
+
+        Ok(MoveFile {
+            match_id: self.id.clone(),
+            round,
+            player,
+            choice,
+            signature: to_hex(&signature.to_bytes()),
+        })
+    }
+
+    fn verify_move(&self, move_file: &MoveFile) -> Result<(), String> {
+        if !(1..=2).contains(&move_file.player) {
+            return Err(format!("no player {} in the match", move_file.player));
+        }
+
+        if move_file.match_id != self.id {
+            return Err(format!(
+                "move of player {} is for another match",
+                move_file.player
+            ));
+        }
+
+        if move_file.round != self.next_round() {
+            return Err(format!(
+                "move of player {} is for round {}, round {} is next",
+                move_file.player,
+                move_file.round,
+                self.next_round()
+            ));
+        }
+
+        self.verify_signature(
+            move_file.round,
+            move_file.player,
+            move_file.choice,
+            &move_file.signature,
+        )
+    }
+
+    fn verify_signature(
+        &self,
+        round: u8,
+        player: u8,
+        choice: Moves,
+        signature: &str,
+    ) -> Result<(), String> {
+        let public_key: [u8; 32] = self
+            .players
+            .get((player - 1) as usize)
+            .and_then(|player| from_hex(player))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(format!("no key for player {}", player))?;
+        let verifying_key =
+            VerifyingKey::from_bytes(&public_key).map_err(|error| error.to_string())?;
+        let signature: [u8; 64] = from_hex(signature)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or("malformed signature")?;
+
+        let payload = move_payload(&self.id, round, player, choice);
+
+        verifying_key
+            .verify(payload.as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| {
+                format!(
+                    "bad signature on the move of player {} in round {}",
+                    player, round
+                )
+            })
+    }
+
+    // Neither player can steer the dice: the seed is part of the match id,
+    // and each signature is only known once its move is sent
+    fn round_rng(&self, signatures: &[String; 2]) -> StdRng {
+        let preimage = format!("{}:{}:{}", self.seed, signatures[0], signatures[1]);
+
+        StdRng::from_seed(*blake3::hash(preimage.as_bytes()).as_bytes())
+    }
+
+    // The duel as it stands, rebuilt from the seed and the signed history.
+    // Fails on anything the players didn't sign or the dice didn't roll
+    pub fn replay(&self) -> Result<Duel, String> {
+        if self.id != match_id(self.nonce, self.seed, &self.players) {
+            return Err("the match id doesn't fit its seed and players".into());
+        }
+
+        let mut duel = Duel::default();
+
+        for entry in &self.history {
+            if duel.result.is_some() {
+                return Err(format!("round {} was played after the end", entry.round));
+            }
+
+            if entry.round != duel.round {
+                return Err(format!(
+                    "round {} is recorded as round {}",
+                    duel.round, entry.round
+                ));
+            }
+
+            for (index, (&choice, signature)) in
+                entry.choices.iter().zip(&entry.signatures).enumerate()
+            {
+                self.verify_signature(entry.round, index as u8 + 1, choice, signature)?;
+            }
+
+            let report = duel.play_round(
+                entry.choices.map(PlayerStates::from),
+                &mut self.round_rng(&entry.signatures),
+            );
+            let health = duel.fighters.clone().map(|fighter| fighter.health);
+
+            if report.states != entry.states
+                || report.buffs != entry.buffs
+                || report.damage_taken != entry.damage_taken
+                || health != entry.health
+            {
+                return Err(format!("round {} doesn't match its moves", entry.round));
+            }
+        }
+
+        let winner = duel.result.and_then(|result| result.winner());
+
+        if self.finished != duel.result.is_some() || self.winner != winner {
+            return Err("the result doesn't match the history".into());
+        }
+
+        Ok(duel)
+    }
+
+    pub fn resolve(&mut self, moves: [&MoveFile; 2]) -> Result<&CorrespondenceRound, String> {
+        if self.finished {
+            return Err("the match is over".into());
+        }
+
+        let mut duel = self.replay()?;
+
+        for move_file in moves {
+            self.verify_move(move_file)?;
+        }
+
+        let mut signed = [None, None];
+
+        for move_file in moves {
+            signed[(move_file.player - 1) as usize] =
+                Some((move_file.choice, move_file.signature.clone()));
+        }
+
+        let [Some((first, first_signature)), Some((second, second_signature))] = signed else {
+            return Err("both players need a move".into());
+        };
+
+        let signatures = [first_signature, second_signature];
+        let round = duel.round;
+        let report = duel.play_round(
+            [first, second].map(PlayerStates::from),
+            &mut self.round_rng(&signatures),
+        );
+
+        self.history.push(CorrespondenceRound {
+            round,
+            choices: [first, second],
+            signatures,
+            states: report.states,
+            buffs: report.buffs,
+            damage_taken: report.damage_taken,
+            health: duel.fighters.clone().map(|fighter| fighter.health),
+        });
+
+        if let Some(result) = duel.result {
+            self.finished = true;
+            self.winner = result.winner();
+        }
+
+        Ok(self.history.last().unwrap())
+    }
+}
+
+// A round resolved by the game, shown once the menu is reached
+#[derive(Resource)]
+pub struct CorrespondenceMatch {
+    pub path: PathBuf,
+    pub match_file: MatchFile,
+}
+
+impl CorrespondenceMatch {
+    // Loads the match and both moves, resolves the round and writes the
+    // match file back
+    pub fn resolve_files(path: PathBuf, moves: [PathBuf; 2]) -> Result<Self, String> {
+        let mut match_file: MatchFile = read_json(&path)?;
+        let moves: [MoveFile; 2] = [read_json(&moves[0])?, read_json(&moves[1])?];
+
+        match_file.resolve([&moves[0], &moves[1]])?;
+
+        let json = serde_json::to_string_pretty(&match_file).map_err(|error| error.to_string())?;
+        fs::write(&path, json)
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))?;
+
+        Ok(CorrespondenceMatch { path, match_file })
+    }
+
+    // `--correspondence MATCH MOVE MOVE` on the command line
+    pub fn from_args() -> Option<Result<Self, String>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--correspondence" {
+                let files: Vec<PathBuf> = args.by_ref().take(3).map(PathBuf::from).collect();
+
+                return Some(match <[PathBuf; 3]>::try_from(files) {
+                    Ok([path, first, second]) => Self::resolve_files(path, [first, second]),
+                    Err(_) => Err("--correspondence needs a match file and two move files".into()),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &PathBuf) -> Result<T, String> {
+    let bytes =
+        fs::read(path).map_err(|error| format!("Could not read {}: {}", path.display(), error))?;
+
+    serde_json::from_slice(&bytes)
+        .map_err(|error| format!("Could not parse {}: {}", path.display(), error))
+}
+
+fn match_id(nonce: u64, seed: u64, players: &[String; 2]) -> String {
+    let preimage = format!("{}:{}:{}:{}", nonce, seed, players[0], players[1]);
+
+    blake3::hash(preimage.as_bytes()).to_hex()[..32].to_string()
+}
+
+fn move_payload(match_id: &str, round: u8, player: u8, choice: Moves) -> String {
+    format!("{}:{}:{}:{:?}", match_id, round, player, choice)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+pub(super) fn plugin(app: &mut App) {
+    match CorrespondenceMatch::from_args() {
+        Some(Ok(correspondence)) => {
+            info!(
+                "Round {} resolved, {} updated",
+                correspondence.match_file.history.len(),
+                correspondence.path.display()
+            );
+            app.insert_resource(correspondence);
+        }
+        Some(Err(error)) => error!("Could not resolve the correspondence round: {}", error),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_match() -> (MatchFile, [SigningKey; 2]) {
+        let keys = [generate_key(), generate_key()];
+        let match_file = MatchFile::new(keys.each_ref().map(public_key), 42);
+
+        (match_file, keys)
+    }
+
+    fn sign_round(
+        match_file: &MatchFile,
+        keys: &[SigningKey; 2],
+        choices: [Moves; 2],
+    ) -> [MoveFile; 2] {
+        [0, 1].map(|index| match_file.sign_move(&keys[index], choices[index]).unwrap())
+    }
+
+    #[test]
+    fn move_of_a_missing_player_is_refused() {
+        let (mut match_file, keys) = new_match();
+        let [first, mut second] = sign_round(&match_file, &keys, [Moves::Attack, Moves::Idle]);
+
+        second.player = 3;
+
+        assert!(match_file.resolve([&first, &second]).is_err());
+        assert!(match_file.history.is_empty());
+    }
+
+    #[test]
+    fn move_claiming_the_other_player_is_refused() {
+        let (mut match_file, keys) = new_match();
+        let [first, _] = sign_round(&match_file, &keys, [Moves::Attack, Moves::Idle]);
+        let mut impostor = first.clone();
+
+        impostor.player = 2;
+
+        assert!(match_file.resolve([&first, &impostor]).is_err());
+    }
+
+    #[test]
+    fn move_for_another_round_is_refused() {
+        let (mut match_file, keys) = new_match();
+        let [first, mut second] = sign_round(&match_file, &keys, [Moves::Attack, Moves::Idle]);
+
+        second.round = 2;
+
+        assert!(match_file.resolve([&first, &second]).is_err());
+    }
+
+    #[test]
+    fn forged_move_is_refused() {
+        let (mut match_file, keys) = new_match();
+        let [first, mut second] = sign_round(&match_file, &keys, [Moves::Attack, Moves::Idle]);
+
+        second.choice = Moves::Dodge;
+
+        assert!(match_file.resolve([&first, &second]).is_err());
+    }
+
+    #[test]
+    fn same_history_replays_to_the_same_duel() {
+        let (mut match_file, keys) = new_match();
+
+        for choices in [[Moves::Attack, Moves::Dodge], [Moves::Buff, Moves::Attack]] {
+            let moves = sign_round(&match_file, &keys, choices);
+            match_file.resolve([&moves[0], &moves[1]]).unwrap();
+        }
+
+        let copy: MatchFile =
+            serde_json::from_str(&serde_json::to_string(&match_file).unwrap()).unwrap();
+        let [first, second] = [&match_file, &copy].map(|match_file| match_file.replay().unwrap());
+
+        assert_eq!(first.round, second.round);
+        assert_eq!(first.result, second.result);
+
+        for (a, b) in first.fighters.iter().zip(&second.fighters) {
+            assert_eq!(
+                (a.health, a.bullets, a.dodges),
+                (b.health, b.bullets, b.dodges)
+            );
+        }
+    }
+
+    #[test]
+    fn rewritten_history_fails_the_replay() {
+        let (mut match_file, keys) = new_match();
+        let moves = sign_round(&match_file, &keys, [Moves::Attack, Moves::Dodge]);
+        match_file.resolve([&moves[0], &moves[1]]).unwrap();
+
+        let mut changed_choice = match_file.clone();
+        changed_choice.history[0].choices[1] = Moves::Idle;
+        assert!(changed_choice.replay().is_err());
+
+        let mut changed_seed = match_file.clone();
+        changed_seed.seed += 1;
+        assert!(changed_seed.replay().is_err());
+
+        let mut changed_outcome = match_file.clone();
+        changed_outcome.history[0].health[0] = 0;
+        assert!(changed_outcome.replay().is_err());
+
+        assert!(match_file.replay().is_ok());
+    }
+}
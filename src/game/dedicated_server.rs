@@ -0,0 +1,556 @@
+// Dedicated server: pairs TCP clients two by two and resolves their duels
+// itself, so clients only ever send their bets.
+//
+//     cargo run --bin server -- --address 0.0.0.0:7780 --log results.jsonl
+//
+// Clients read one JSON `ServerMessage` per line. `bet` messages carry the
+// same fields as the external bots' requests, and are answered with the same
+// {"action": "Attack"} line, or just `attack` when typing in a terminal.
+// Answers after the betting timer are ignored and the player idles.
+//
+// The game joins a server as one of its players:
+//
+//     hit_a_key_game --server 127.0.0.1:7780
+use std::{
+    fs::OpenOptions,
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Mutex,
+    },
+    thread,
+};
+
+use rand::rngs::StdRng;
+
+use super::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Waiting,
+    MatchStart {
+        match_id: u64,
+        player: u8,
+    },
+    Bet(BotRequest),
+    RoundResult {
+        round: u8,
+        outcome: RoundOutcome,
+        health: [u8; 2],
+    },
+    OpponentLeft,
+    GameOver {
+        winner: Option<u8>,
+        cause: Option<GameOverCauses>,
+    },
+}
+
+#[derive(Serialize)]
+struct MatchLog {
+    match_id: u64,
+    players: [SocketAddr; 2],
+    rounds: u8,
+    winner: Option<u8>,
+    cause: Option<GameOverCauses>,
+    forfeit: bool,
+}
+
+pub struct ServerClient {
+    address: SocketAddr,
+    messages: Sender<Vec<u8>>,
+    lines: Mutex<Receiver<String>>,
+}
+
+impl ServerClient {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        let address = stream.peer_addr()?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // A slow client would stall every match, so writes get their own thread
+        let (messages, pending) = mpsc::channel::<Vec<u8>>();
+
+        thread::spawn(move || {
+            for line in pending {
+                if writer.write_all(&line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ServerClient {
+            address,
+            messages,
+            lines: Mutex::new(receiver),
+        })
+    }
+
+    fn send(&self, message: &ServerMessage) -> bool {
+        let Ok(mut line) = serde_json::to_vec(message) else {
+            return false;
+        };
+        line.push(b'\n');
+
+        self.messages.send(line).is_ok()
+    }
+
+    // First readable move sent since the previous call, `None` once gone
+    fn read_move(&self) -> Option<Option<Moves>> {
+        let lines = self.lines.lock().ok()?;
+        let mut choice = None;
+
+        loop {
+            match lines.try_recv() {
+                Ok(line) => choice = choice.or(parse_move(&line)),
+                Err(TryRecvError::Empty) => return Some(choice),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+fn parse_move(line: &str) -> Option<Moves> {
+    serde_json::from_str::<BotResponse>(line)
+        .map(|response| response.action)
+        .ok()
+        .or_else(|| {
+            MOVES
+                .into_iter()
+                .find(|choice| format!("{:?}", choice).eq_ignore_ascii_case(line.trim()))
+        })
+}
+
+#[derive(Resource)]
+pub struct DedicatedServer {
+    pub address: SocketAddr,
+    pub betting_time: f32,
+    pub log: Option<PathBuf>,
+    incoming: Mutex<Receiver<TcpStream>>,
+    waiting: Option<ServerClient>,
+    next_match: u64,
+    rng: StdRng,
+}
+
+impl DedicatedServer {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if sender.send(stream).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(DedicatedServer {
+            address,
+            betting_time: DEFAULT_BETTING_TIMER,
+            log: None,
+            incoming: Mutex::new(receiver),
+            waiting: None,
+            next_match: 1,
+            rng: StdRng::from_os_rng(),
+        })
+    }
+
+    // `[--address ADDRESS] [--betting-time SECONDS] [--log FILE] [--seed S]`
+    pub fn from_args() -> Result<Self, String> {
+        let mut address = DEDICATED_SERVER_ADDRESS.to_string();
+        let mut betting_time = DEFAULT_BETTING_TIMER;
+        let mut log = None;
+        let mut seed = None;
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or(format!("{} needs a value", arg))?;
+
+            match arg.as_str() {
+                "--address" => address = value,
+                "--betting-time" => {
+                    betting_time = value
+                        .parse()
+                        .map_err(|_| format!("bad betting time {}", value))?
+                }
+                "--log" => log = Some(PathBuf::from(value)),
+                "--seed" => seed = Some(value.parse().map_err(|_| format!("bad seed {}", value))?),
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+
+        let mut server = DedicatedServer::bind(&address).map_err(|error| error.to_string())?;
+        server.betting_time = betting_time;
+        server.log = log;
+
+        if let Some(seed) = seed {
+            server.rng = StdRng::seed_from_u64(seed);
+        }
+
+        Ok(server)
+    }
+
+    fn accept(&self) -> Option<TcpStream> {
+        self.incoming.lock().ok()?.try_recv().ok()
+    }
+
+    fn write_log(&self, entry: &MatchLog) {
+        let Some(path) = &self.log else {
+            return;
+        };
+
+        let written = serde_json::to_string(entry)
+            .map_err(io::Error::other)
+            .and_then(|line| {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "{}", line)
+            });
+
+        if let Err(error) = written {
+            warn!("Could not log match {}: {}", entry.match_id, error);
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ServerMatch {
+    id: u64,
+    clients: [ServerClient; 2],
+    duel: Duel,
+    rng: StdRng,
+    timer: Timer,
+    choices: [Option<Moves>; 2],
+    last_round: Option<RoundReport>,
+}
+
+impl ServerMatch {
+    fn broadcast(&self, message: impl Fn(u8) -> ServerMessage) {
+        for (index, client) in self.clients.iter().enumerate() {
+            client.send(&message(index as u8 + 1));
+        }
+    }
+
+    fn request_bets(&mut self) {
+        let betting_time = self.timer.duration().as_secs_f32();
+        let views = [1, 2].map(|player| self.duel.view(player));
+        let outcomes = [1, 2].map(|player| {
+            self.last_round
+                .map(|report: RoundReport| report.outcome(player))
+        });
+
+        // Anything sent before the request is too early for this round
+        for client in &self.clients {
+            client.read_move();
+        }
+
+        self.broadcast(|player| {
+            let index = (player - 1) as usize;
+
+            ServerMessage::Bet(BotRequest {
+                player,
+                round: views[index].round,
+                max_round: N_MAX_ROUND,
+                time_limit: betting_time,
+                me: views[index].me,
+                opponent: views[index].opponent,
+                last_round: outcomes[index],
+            })
+        });
+
+        self.choices = [None; 2];
+        self.timer.reset();
+    }
+
+    fn log(&self, winner: Option<u8>, forfeit: bool) -> MatchLog {
+        MatchLog {
+            match_id: self.id,
+            players: [self.clients[0].address, self.clients[1].address],
+            rounds: self.duel.round,
+            winner,
+            cause: self.duel.ending,
+            forfeit,
+        }
+    }
+}
+
+fn accept_clients(mut server: ResMut<DedicatedServer>, mut commands: Commands) {
+    while let Some(stream) = server.accept() {
+        let client = match ServerClient::new(stream) {
+            Ok(client) => client,
+            Err(error) => {
+                warn!("Could not accept client: {}", error);
+                continue;
+            }
+        };
+
+        // A waiting client that left is replaced
+        let waiting = server
+            .waiting
+            .take()
+            .filter(|waiting| waiting.read_move().is_some());
+
+        let Some(waiting) = waiting else {
+            info!("{} is waiting for an opponent", client.address);
+            client.send(&ServerMessage::Waiting);
+            server.waiting = Some(client);
+            continue;
+        };
+
+        let id = server.next_match;
+        server.next_match += 1;
+
+        let mut server_match = ServerMatch {
+            id,
+            clients: [waiting, client],
+            duel: Duel::default(),
+            rng: StdRng::seed_from_u64(server.rng.random()),
+            timer: Timer::from_seconds(server.betting_time, TimerMode::Once),
+            choices: [None; 2],
+            last_round: None,
+        };
+
+        info!(
+            "Match {} starts: {} against {}",
+            id, server_match.clients[0].address, server_match.clients[1].address
+        );
+
+        server_match.broadcast(|player| ServerMessage::MatchStart {
+            match_id: id,
+            player,
+        });
+        server_match.request_bets();
+        commands.spawn(server_match);
+    }
+}
+
+fn collect_bets(
+    server: Res<DedicatedServer>,
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ServerMatch)>,
+) {
+    for (entity, mut server_match) in &mut query {
+        server_match.timer.tick(time.delta());
+
+        let mut left = None;
+
+        for index in 0..2 {
+            match server_match.clients[index].read_move() {
+                Some(choice) => {
+                    // First answer counts, changing one's mind is not allowed
+                    if server_match.choices[index].is_none() {
+                        server_match.choices[index] = choice;
+                    }
+                }
+                None => left = Some(index),
+            }
+        }
+
+        if let Some(index) = left {
+            let winner = 2 - index as u8;
+
+            info!(
+                "Match {}: {} left, player {} wins",
+                server_match.id, server_match.clients[index].address, winner
+            );
+
+            server_match.clients[1 - index].send(&ServerMessage::OpponentLeft);
+            server_match.clients[1 - index].send(&ServerMessage::GameOver {
+                winner: Some(winner),
+                cause: None,
+            });
+            server.write_log(&server_match.log(Some(winner), true));
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let answered = server_match.choices.iter().all(Option::is_some);
+
+        if answered || server_match.timer.finished() {
+            resolve_round(&server, &mut server_match);
+
+            if server_match.duel.result.is_some() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn resolve_round(server: &DedicatedServer, server_match: &mut ServerMatch) {
+    let actions = server_match
+        .choices
+        .map(|choice| choice.map_or(PlayerStates::Idle, PlayerStates::from));
+    let ServerMatch { duel, rng, .. } = server_match;
+    let round = duel.round;
+    let report = duel.play_round(actions, rng);
+    let health = duel.fighters.clone().map(|fighter| fighter.health);
+
+    server_match.last_round = Some(report);
+    server_match.broadcast(|player| ServerMessage::RoundResult {
+        round,
+        outcome: report.outcome(player),
+        health,
+    });
+
+    let Some(result) = server_match.duel.result else {
+        server_match.request_bets();
+        return;
+    };
+
+    let winner = result.winner();
+    let cause = server_match.duel.ending;
+
+    match winner {
+        Some(winner) => info!("Match {}: player {} wins", server_match.id, winner),
+        None => info!("Match {}: tie", server_match.id),
+    }
+
+    server_match.broadcast(|_| ServerMessage::GameOver { winner, cause });
+    server.write_log(&server_match.log(winner, false));
+}
+
+// The game's end of a server match
+#[derive(Resource)]
+pub struct ServerConnection {
+    pub address: String,
+    // Seat given by the server once an opponent is found
+    pub player: Option<u8>,
+    // Running while the current bet can still be sent
+    pub bet_timer: Option<Timer>,
+    stream: TcpStream,
+    answers: Sender<Vec<u8>>,
+    messages: Mutex<Receiver<ServerMessage>>,
+}
+
+impl ServerConnection {
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream.try_clone()?;
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                let Ok(message) = serde_json::from_str::<ServerMessage>(&line) else {
+                    continue;
+                };
+
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (answers, pending) = mpsc::channel::<Vec<u8>>();
+
+        thread::spawn(move || {
+            for line in pending {
+                if writer.write_all(&line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ServerConnection {
+            address: address.to_string(),
+            player: None,
+            bet_timer: None,
+            stream,
+            answers,
+            messages: Mutex::new(receiver),
+        })
+    }
+
+    // `--server ADDRESS` on the command line
+    pub fn from_args() -> Option<io::Result<Self>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--server" {
+                return Some(match args.next() {
+                    Some(address) => ServerConnection::connect(&address),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--server needs an address",
+                    )),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn send_move(&self, choice: Moves) -> bool {
+        let Ok(mut line) = serde_json::to_vec(&BotResponse { action: choice }) else {
+            return false;
+        };
+        line.push(b'\n');
+
+        self.answers.send(line).is_ok()
+    }
+
+    // Messages received since the previous call, `None` once the server is gone
+    pub fn receive(&self) -> Option<Vec<ServerMessage>> {
+        let messages = self.messages.lock().ok()?;
+        let mut received = vec![];
+
+        loop {
+            match messages.try_recv() {
+                Ok(message) => received.push(message),
+                Err(TryRecvError::Empty) => return Some(received),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+// The reading thread holds the socket open until it is shut down
+impl Drop for ServerConnection {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+fn exit_app(mut ev_exit: EventWriter<AppExit>) {
+    ev_exit.send(AppExit::error());
+}
+
+pub(super) fn plugin(app: &mut App) {
+    match DedicatedServer::from_args() {
+        Ok(server) => {
+            info!("Dedicated server listening on {}", server.address);
+            app.insert_resource(server);
+        }
+        Err(error) => {
+            error!("Could not start dedicated server: {}", error);
+            app.add_systems(Startup, exit_app);
+            return;
+        }
+    }
+
+    app.add_systems(Update, (accept_clients, collect_bets).chain());
+}
+
+pub(super) fn client_plugin(app: &mut App) {
+    match ServerConnection::from_args() {
+        Some(Ok(connection)) => {
+            info!("Joining the server on {}", connection.address);
+            app.insert_resource(connection);
+        }
+        Some(Err(error)) => error!("Could not join the server: {}", error),
+        None => {}
+    }
+}
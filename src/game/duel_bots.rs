@@ -0,0 +1,283 @@
+// Bots playing whole duels outside of the game, for the headless tools
+use std::{fs, io, path::Path, time::Duration};
+
+use rand::rngs::StdRng;
+use serde::de::DeserializeOwned;
+
+use super::*;
+
+pub trait DuelBot {
+    // Called before each duel with the seat the bot plays from
+    fn reset(&mut self, _player: u8) {}
+
+    fn decide(
+        &mut self,
+        view: &DuelView,
+        last_round: Option<RoundOutcome>,
+        rng: &mut StdRng,
+    ) -> PlayerStates;
+}
+
+struct BuiltinBot(Difficulties);
+
+impl DuelBot for BuiltinBot {
+    fn decide(
+        &mut self,
+        view: &DuelView,
+        _: Option<RoundOutcome>,
+        rng: &mut StdRng,
+    ) -> PlayerStates {
+        ai::decide(self.0, view, rng)
+    }
+}
+
+struct PersonalityBot(Personality);
+
+impl DuelBot for PersonalityBot {
+    fn decide(
+        &mut self,
+        view: &DuelView,
+        last_round: Option<RoundOutcome>,
+        rng: &mut StdRng,
+    ) -> PlayerStates {
+        self.0
+            .decide(view, last_round.map(|outcome| outcome.opponent), rng)
+    }
+}
+
+// Keeps its model between duels, like the in-game learner does between matches
+#[derive(Default)]
+struct LearnerBot {
+    model: OpponentModel,
+    previous_view: Option<DuelView>,
+    opponent_previous: Option<Moves>,
+}
+
+impl DuelBot for LearnerBot {
+    fn reset(&mut self, _player: u8) {
+        self.previous_view = None;
+        self.opponent_previous = None;
+    }
+
+    fn decide(
+        &mut self,
+        view: &DuelView,
+        last_round: Option<RoundOutcome>,
+        rng: &mut StdRng,
+    ) -> PlayerStates {
+        if let (Some(previous_view), Some(outcome)) = (self.previous_view, last_round) {
+            let context = ModelContext {
+                view: previous_view.flipped(),
+                previous: self.opponent_previous,
+            };
+
+            self.model.observe(&context, outcome.opponent);
+            self.opponent_previous = Some(outcome.opponent);
+        }
+
+        self.previous_view = Some(*view);
+
+        let prediction = self.model.predict(&ModelContext {
+            view: view.flipped(),
+            previous: self.opponent_previous,
+        });
+
+        match prediction {
+            Some(prediction) => best_response(view, prediction),
+            None => ai::decide(Difficulties::Hard, view, rng),
+        }
+    }
+}
+
+// Same protocol as in game, the betting timer becomes a blocking timeout
+struct ProcessBot {
+    process: BotProcess,
+    player: u8,
+}
+
+impl DuelBot for ProcessBot {
+    fn reset(&mut self, player: u8) {
+        self.player = player;
+    }
+
+    fn decide(
+        &mut self,
+        view: &DuelView,
+        last_round: Option<RoundOutcome>,
+        _: &mut StdRng,
+    ) -> PlayerStates {
+        let request = BotRequest {
+            player: self.player,
+            round: view.round,
+            max_round: N_MAX_ROUND,
+            time_limit: DEFAULT_BETTING_TIMER,
+            me: view.me,
+            opponent: view.opponent,
+            last_round,
+        };
+
+        self.process.drain();
+
+        if self.process.send(&request).is_err() {
+            return PlayerStates::Idle;
+        }
+
+        self.process
+            .read_timeout(Duration::from_secs_f32(DEFAULT_BETTING_TIMER))
+            .and_then(|line| serde_json::from_str::<BotResponse>(&line).ok())
+            .map_or(PlayerStates::Idle, |response| {
+                PlayerStates::from(response.action)
+            })
+    }
+}
+
+#[derive(Clone)]
+pub enum BotKind {
+    Builtin(Difficulties),
+    Learner,
+    Personality(Personality),
+    External(ExternalBotConfig),
+}
+
+#[derive(Clone)]
+pub struct RegisteredBot {
+    pub name: String,
+    pub kind: BotKind,
+}
+
+impl RegisteredBot {
+    pub fn spawn(&self) -> io::Result<Box<dyn DuelBot>> {
+        Ok(match &self.kind {
+            BotKind::Builtin(difficulty) => Box::new(BuiltinBot(*difficulty)),
+            BotKind::Learner => Box::new(LearnerBot::default()),
+            BotKind::Personality(personality) => Box::new(PersonalityBot(personality.clone())),
+            BotKind::External(config) => Box::new(ProcessBot {
+                process: BotProcess::spawn(config)?,
+                player: 1,
+            }),
+        })
+    }
+}
+
+// Same data files as the game loads, read straight from the assets folder
+fn read_folder<T: DeserializeOwned>(folder: &str, extension: &str) -> Vec<T> {
+    let Ok(entries) = fs::read_dir(Path::new(ASSETS_DIR).join(folder)) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(extension))
+        .collect();
+
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let parsed = fs::read(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice(&bytes).map_err(|error| error.to_string())
+                });
+
+            match parsed {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    warn!("Skipping {}: {}", path.display(), error);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+// The tools run without Bevy's log plugin, skipped files still get reported
+pub fn log_to_stderr() {
+    bevy::log::tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .without_time()
+        .init();
+}
+
+pub fn registered_bots(include_external: bool) -> Vec<RegisteredBot> {
+    let mut bots: Vec<RegisteredBot> =
+        [Difficulties::Easy, Difficulties::Normal, Difficulties::Hard]
+            .into_iter()
+            .map(|difficulty| RegisteredBot {
+                name: format!("{:?}", difficulty),
+                kind: BotKind::Builtin(difficulty),
+            })
+            .collect();
+
+    bots.push(RegisteredBot {
+        name: "Learning".to_string(),
+        kind: BotKind::Learner,
+    });
+
+    bots.extend(
+        read_folder::<Personality>("personalities", ".personality.json")
+            .into_iter()
+            .filter(|personality| match personality.validate() {
+                Ok(()) => true,
+                Err(error) => {
+                    warn!("Skipping {}: {}", personality.name, error);
+                    false
+                }
+            })
+            .map(|personality| RegisteredBot {
+                name: personality.name.clone(),
+                kind: BotKind::Personality(personality),
+            }),
+    );
+
+    if include_external {
+        bots.extend(
+            read_folder::<ExternalBotConfig>("bots", ".bot.json")
+                .into_iter()
+                .map(|config| RegisteredBot {
+                    name: config.name.clone(),
+                    kind: BotKind::External(config),
+                }),
+        );
+    }
+
+    bots
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DuelRecord {
+    pub result: MatchResult,
+    pub rounds: u8,
+}
+
+pub fn play_duel(mut bots: [&mut dyn DuelBot; 2], rng: &mut StdRng) -> DuelRecord {
+    let mut duel = Duel::default();
+    let mut last_round: Option<RoundReport> = None;
+
+    for (index, bot) in bots.iter_mut().enumerate() {
+        bot.reset(index as u8 + 1);
+    }
+
+    loop {
+        let mut actions = [PlayerStates::Idle; 2];
+
+        for (index, bot) in bots.iter_mut().enumerate() {
+            let player = index as u8 + 1;
+            let outcome = last_round.map(|report| report.outcome(player));
+
+            actions[index] = bot.decide(&duel.view(player), outcome, rng);
+        }
+
+        let report = duel.play_round(actions, rng);
+
+        if let Some(result) = duel.result {
+            return DuelRecord {
+                result,
+                rounds: duel.round,
+            };
+        }
+
+        last_round = Some(report);
+    }
+}
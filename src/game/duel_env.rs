@@ -0,0 +1,219 @@
+// Reinforcement learning environment over the duel rules.
+//
+// Observations are fixed size vectors, one per player and seen from their side:
+// for "me" then "opponent", `FIGHTER_FEATURES` values each
+//   health, max health, bullets, dodges, damage, luck dice, marksmanship dice,
+//   one-hot of the buff drawn last round (6), one-hot of the move played last round (4)
+// followed by the round and the number of rounds left.
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::*;
+
+const FIGHTER_FEATURES: usize = 7 + BUFFES.len() + MOVES.len();
+pub const OBSERVATION_SIZE: usize = 2 * FIGHTER_FEATURES + 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observation {
+    pub players: [[f32; OBSERVATION_SIZE]; 2],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepInfo {
+    pub round: u8,
+    pub result: Option<MatchResult>,
+    // Moves after buffs and empty resources had their say
+    pub states: [PlayerStates; 2],
+    pub buffs: [Option<Buffes>; 2],
+    pub damage_taken: [u8; 2],
+    // Set by the vectorized environment, which resets finished duels on its own
+    pub final_observation: Option<Observation>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepResult {
+    pub observation: Observation,
+    pub rewards: [f32; 2],
+    pub done: bool,
+    pub info: StepInfo,
+}
+
+pub struct DuelEnv {
+    duel: Duel,
+    last_round: Option<RoundReport>,
+    rng: StdRng,
+}
+
+impl DuelEnv {
+    pub fn new(seed: u64) -> Self {
+        DuelEnv {
+            duel: Duel::default(),
+            last_round: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        *self = DuelEnv::new(seed);
+
+        self.observation()
+    }
+
+    pub fn step(&mut self, action_p1: Moves, action_p2: Moves) -> StepResult {
+        // A finished duel stays finished until the next reset
+        if let Some(result) = self.duel.result {
+            return StepResult {
+                observation: self.observation(),
+                rewards: [0.; 2],
+                done: true,
+                info: self.info(Some(result)),
+            };
+        }
+
+        let actions = [action_p1, action_p2].map(PlayerStates::from);
+        let report = self.duel.play_round(actions, &mut self.rng);
+
+        self.last_round = Some(report);
+
+        let rewards = match self.duel.result {
+            Some(MatchResult::Winner(1)) => [1., -1.],
+            Some(MatchResult::Winner(_)) => [-1., 1.],
+            _ => [0.; 2],
+        };
+
+        StepResult {
+            observation: self.observation(),
+            rewards,
+            done: self.duel.result.is_some(),
+            info: self.info(self.duel.result),
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        Observation {
+            players: [1, 2].map(|player| self.encode(player)),
+        }
+    }
+
+    fn info(&self, result: Option<MatchResult>) -> StepInfo {
+        StepInfo {
+            round: self.duel.round,
+            result,
+            states: self
+                .last_round
+                .map_or([PlayerStates::Idle; 2], |report| report.states),
+            buffs: self.last_round.map_or([None; 2], |report| report.buffs),
+            damage_taken: self.last_round.map_or([0; 2], |report| report.damage_taken),
+            final_observation: None,
+        }
+    }
+
+    fn encode(&self, player: u8) -> [f32; OBSERVATION_SIZE] {
+        let me = (player - 1) as usize;
+        let mut features = [0.; OBSERVATION_SIZE];
+
+        for (side, index) in [me, 1 - me].into_iter().enumerate() {
+            let fighter = &self.duel.fighters[index];
+            let offset = side * FIGHTER_FEATURES;
+            let resources = [
+                fighter.health,
+                fighter.max_health,
+                fighter.bullets,
+                fighter.dodges,
+                fighter.damage,
+                fighter.luck.value,
+                fighter.marksmanship.value,
+            ];
+
+            for (feature, value) in features[offset..].iter_mut().zip(resources) {
+                *feature = value as f32;
+            }
+
+            if let Some(report) = self.last_round {
+                let buff = report.buffs[index]
+                    .and_then(|buff| BUFFES.iter().position(|other| *other == buff));
+                let moves = MOVES
+                    .iter()
+                    .position(|other| *other == Moves::from(report.states[index]));
+
+                if let Some(buff) = buff {
+                    features[offset + resources.len() + buff] = 1.;
+                }
+
+                if let Some(moves) = moves {
+                    features[offset + resources.len() + BUFFES.len() + moves] = 1.;
+                }
+            }
+        }
+
+        features[2 * FIGHTER_FEATURES] = self.duel.round as f32;
+        features[2 * FIGHTER_FEATURES + 1] = N_MAX_ROUND.saturating_sub(self.duel.round) as f32;
+
+        features
+    }
+}
+
+// Many environments stepped together, finished duels start over on their own
+pub struct VecDuelEnv {
+    envs: Vec<DuelEnv>,
+    seeds: Vec<u64>,
+}
+
+impl VecDuelEnv {
+    pub fn new(count: usize, seed: u64) -> Self {
+        let seeds: Vec<u64> = (0..count as u64)
+            .map(|index| seed.wrapping_add(index))
+            .collect();
+
+        VecDuelEnv {
+            envs: seeds.iter().map(|seed| DuelEnv::new(*seed)).collect(),
+            seeds,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    // Environment `i` is seeded with `seed + i`
+    pub fn reset(&mut self, seed: u64) -> Vec<Observation> {
+        self.envs
+            .iter_mut()
+            .zip(&mut self.seeds)
+            .enumerate()
+            .map(|(index, (env, env_seed))| {
+                *env_seed = seed.wrapping_add(index as u64);
+                env.reset(*env_seed)
+            })
+            .collect()
+    }
+
+    pub fn step(&mut self, actions: &[(Moves, Moves)]) -> Vec<StepResult> {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "one action pair per environment"
+        );
+
+        self.envs
+            .iter_mut()
+            .zip(&mut self.seeds)
+            .zip(actions)
+            .map(|((env, seed), (action_p1, action_p2))| {
+                let mut step = env.step(*action_p1, *action_p2);
+
+                if step.done {
+                    // Fresh seeds keep every episode of an environment different
+                    *seed = seed.wrapping_add(1 << 32);
+                    step.info.final_observation = Some(step.observation);
+                    step.observation = env.reset(*seed);
+                }
+
+                step
+            })
+            .collect()
+    }
+}
@@ -1,12 +1,12 @@
 use super::*;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum GameOvers {
     Tie,
     Winner,
 }
 
-#[derive(Event)]
+#[derive(Event, Clone, Copy)]
 pub struct GameOverEvent {
     pub player: Option<u8>,
     pub state: GameOvers,
@@ -20,10 +20,12 @@ pub struct PlayerStateChangeEvent {
 #[derive(Event)]
 pub struct AttackEvent {
     pub player: u8,
+    pub attacker: u8,
     pub damage: u8,
     pub marksmanship: Dice,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum DepletedSources {
     Bullets,
     Dodges,
@@ -38,10 +40,19 @@ pub struct DepletedEvent {
 #[derive(Event)]
 pub struct DamageEvent {
     pub player: u8,
+    pub attacker: u8,
     pub value: u8,
     pub marksmanship: Dice,
 }
 
+#[derive(Event)]
+pub struct ShotRolledEvent {
+    pub player: u8,
+    pub attacker: u8,
+    pub damage: u8,
+    pub roll: HitRoll,
+}
+
 #[derive(Event)]
 pub struct MissedEvent {
     pub player: u8,
@@ -69,6 +80,7 @@ pub(super) fn plugin(app: &mut App) {
     app.add_event::<AttackEvent>();
     app.add_event::<DamageEvent>();
     app.add_event::<DepletedEvent>();
+    app.add_event::<ShotRolledEvent>();
     app.add_event::<MissedEvent>();
     app.add_event::<DodgedEvent>();
     app.add_event::<TickPlayerEvent>();
@@ -0,0 +1,175 @@
+// Bots running as child processes, speaking JSON lines over stdin/stdout.
+// Each betting phase the game writes one `BotRequest` line and waits for a
+// `BotResponse` line such as {"action": "Attack"} before the betting timer ends.
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+
+use super::*;
+
+#[derive(Asset, TypePath, Deserialize, Clone)]
+pub struct ExternalBotConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl ExternalBotConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("the bot needs a name".into());
+        }
+
+        if self.command.trim().is_empty() {
+            return Err(format!("{} has no command to run", self.name));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct RoundOutcome {
+    pub me: Moves,
+    pub opponent: Moves,
+    pub damage_taken: u8,
+    pub damage_dealt: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BotRequest {
+    pub player: u8,
+    pub round: u8,
+    pub max_round: u8,
+    pub time_limit: f32,
+    pub me: FighterView,
+    pub opponent: FighterView,
+    pub last_round: Option<RoundOutcome>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BotResponse {
+    pub action: Moves,
+}
+
+pub struct BotProcess {
+    child: Child,
+    requests: Sender<Vec<u8>>,
+    lines: Mutex<Receiver<String>>,
+}
+
+impl BotProcess {
+    pub fn spawn(config: &ExternalBotConfig) -> io::Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let mut stdin = child.stdin.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let stdout = child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let (sender, receiver) = mpsc::channel();
+
+        // Reading blocks, so it lives on its own thread until the bot exits
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // So does writing, a bot that stops reading can't stall the game
+        let (requests, pending) = mpsc::channel::<Vec<u8>>();
+
+        thread::spawn(move || {
+            for line in pending {
+                if stdin.write_all(&line).and_then(|_| stdin.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(BotProcess {
+            child,
+            requests,
+            lines: Mutex::new(receiver),
+        })
+    }
+
+    pub fn send(&mut self, request: &BotRequest) -> io::Result<()> {
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+
+        self.requests
+            .send(line)
+            .map_err(|_| io::ErrorKind::BrokenPipe.into())
+    }
+
+    pub fn try_read(&self) -> Option<String> {
+        self.lines.lock().ok()?.try_recv().ok()
+    }
+
+    // Blocks until the bot answers, for the headless tools
+    pub fn read_timeout(&self, timeout: Duration) -> Option<String> {
+        self.lines.lock().ok()?.recv_timeout(timeout).ok()
+    }
+
+    // Drops answers that arrived too late for their round
+    pub fn drain(&self) {
+        while self.try_read().is_some() {}
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ExternalBotLibrary {
+    pub bots: Vec<Handle<ExternalBotConfig>>,
+}
+
+#[derive(Default)]
+struct ExternalBotLoader;
+
+impl AssetLoader for ExternalBotLoader {
+    type Asset = ExternalBotConfig;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bot.json"]
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<ExternalBotConfig>();
+    app.init_asset_loader::<ExternalBotLoader>();
+    app.init_resource::<ExternalBotLibrary>();
+}
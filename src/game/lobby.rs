@@ -0,0 +1,142 @@
+// LAN game discovery. An open host broadcasts a `LobbyAnnouncement` datagram
+// every second on `LOBBY_PORT`, the lobby screen listens and lists them.
+// Joining one, by the list or by its room code, opens a `NetSession`.
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LobbyAnnouncement {
+    pub room: String,
+    pub host_name: String,
+    pub port: u16,
+    pub rules: String,
+}
+
+pub struct LobbyGame {
+    pub announcement: LobbyAnnouncement,
+    pub address: SocketAddr,
+    last_seen: Instant,
+}
+
+#[derive(Resource)]
+pub struct LobbyBrowser {
+    pub games: Vec<LobbyGame>,
+    socket: UdpSocket,
+}
+
+impl LobbyBrowser {
+    pub fn bind() -> io::Result<Self> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, LOBBY_PORT))?;
+        socket.set_nonblocking(true)?;
+
+        Ok(LobbyBrowser {
+            games: vec![],
+            socket,
+        })
+    }
+
+    pub fn receive(&mut self) {
+        let mut buffer = [0; 1024];
+
+        while let Ok((length, from)) = self.socket.recv_from(&mut buffer) {
+            let Ok(announcement) = serde_json::from_slice::<LobbyAnnouncement>(&buffer[..length])
+            else {
+                continue;
+            };
+
+            let address = SocketAddr::new(from.ip(), announcement.port);
+
+            // Heard on several interfaces, a room is listed once
+            match self
+                .games
+                .iter_mut()
+                .find(|game| game.announcement.room == announcement.room)
+            {
+                Some(game) => {
+                    game.announcement = announcement;
+                    game.address = address;
+                    game.last_seen = Instant::now();
+                }
+                None => self.games.push(LobbyGame {
+                    announcement,
+                    address,
+                    last_seen: Instant::now(),
+                }),
+            }
+        }
+
+        let expiry = Duration::from_secs_f32(LOBBY_EXPIRY);
+        self.games.retain(|game| game.last_seen.elapsed() < expiry);
+    }
+
+    pub fn find(&self, room: &str) -> Option<&LobbyGame> {
+        self.games
+            .iter()
+            .find(|game| game.announcement.room == room)
+    }
+}
+
+#[derive(Resource)]
+pub struct LobbyBeacon {
+    pub announcement: LobbyAnnouncement,
+    socket: UdpSocket,
+    last_sent: Option<Instant>,
+}
+
+impl LobbyBeacon {
+    pub fn new(announcement: LobbyAnnouncement) -> io::Result<Self> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_broadcast(true)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(LobbyBeacon {
+            announcement,
+            socket,
+            last_sent: None,
+        })
+    }
+
+    pub fn announce(&mut self) {
+        let interval = Duration::from_secs_f32(LOBBY_ANNOUNCE_INTERVAL);
+
+        if self
+            .last_sent
+            .is_some_and(|last_sent| last_sent.elapsed() < interval)
+        {
+            return;
+        }
+
+        self.last_sent = Some(Instant::now());
+
+        let Ok(datagram) = serde_json::to_vec(&self.announcement) else {
+            return;
+        };
+
+        // Loopback too, so two instances on one machine find each other
+        for address in [Ipv4Addr::BROADCAST, Ipv4Addr::LOCALHOST] {
+            if let Err(error) = self.socket.send_to(&datagram, (address, LOBBY_PORT)) {
+                warn!("Could not announce game to {}: {}", address, error);
+            }
+        }
+    }
+}
+
+pub fn room_code() -> String {
+    let mut rng = rand::rng();
+
+    (0..ROOM_CODE_LENGTH)
+        .map(|_| rng.random_range(b'A'..=b'Z') as char)
+        .collect()
+}
+
+pub fn host_name() -> String {
+    ["USER", "USERNAME", "HOSTNAME"]
+        .into_iter()
+        .find_map(|variable| std::env::var(variable).ok())
+        .unwrap_or_else(|| "Player".to_string())
+}
@@ -0,0 +1,376 @@
+// Online duels between two machines over UDP.
+// Each peer keeps sending its whole `PeerStatus` as one JSON datagram, so a
+// lost packet is simply covered by the next one. The host (player 1) starts
+// games and picks the seed, the joiner (player 2) follows.
+// Betting choices travel as salted hash commitments first, and each peer only
+// reveals its own choice once it holds the other one's commitment.
+//
+//     hit_a_key_game --host 0.0.0.0:7777
+//     hit_a_key_game --join 192.168.1.10:7777
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NetRoles {
+    Host,
+    Join,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Reveal {
+    pub choice: Moves,
+    pub salt: u128,
+}
+
+impl Reveal {
+    // The player is part of the hash so a commitment can't be echoed back
+    pub fn commitment(&self, game: u32, round: u8, player: u8) -> String {
+        let preimage = format!(
+            "{}:{}:{}:{:?}:{}",
+            game, round, player, self.choice, self.salt
+        );
+
+        blake3::hash(preimage.as_bytes()).to_hex().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub game: u32,
+    pub seed: u64,
+    pub in_menu: bool,
+    pub name: String,
+    pub ready: bool,
+    // One entry per round of the current game
+    pub commitments: Vec<String>,
+    pub reveals: Vec<Reveal>,
+}
+
+#[derive(Resource)]
+pub struct NetSession {
+    pub role: NetRoles,
+    pub address: SocketAddr,
+    pub local: PeerStatus,
+    pub remote: PeerStatus,
+    // Own reveals, published round by round
+    pub secrets: Vec<Reveal>,
+    committed_at: Option<Instant>,
+    socket: UdpSocket,
+    peer: Option<SocketAddr>,
+    last_heard: Option<Instant>,
+    last_sent: Option<Instant>,
+}
+
+impl NetSession {
+    pub fn host(address: &str) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        let address = socket.local_addr()?;
+
+        NetSession::new(NetRoles::Host, socket, address, None)
+    }
+
+    pub fn join(address: &str) -> io::Result<Self> {
+        let address: SocketAddr = address
+            .parse()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+
+        NetSession::new(NetRoles::Join, socket, address, Some(address))
+    }
+
+    fn new(
+        role: NetRoles,
+        socket: UdpSocket,
+        address: SocketAddr,
+        peer: Option<SocketAddr>,
+    ) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+
+        Ok(NetSession {
+            role,
+            address,
+            local: PeerStatus {
+                name: host_name(),
+                ..default()
+            },
+            remote: PeerStatus::default(),
+            secrets: vec![],
+            committed_at: None,
+            socket,
+            peer,
+            last_heard: None,
+            last_sent: None,
+        })
+    }
+
+    // `--host ADDRESS` or `--join ADDRESS` on the command line
+    pub fn from_args() -> Option<io::Result<Self>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let connect = match arg.as_str() {
+                "--host" => NetSession::host,
+                "--join" => NetSession::join,
+                _ => continue,
+            };
+
+            return Some(match args.next() {
+                Some(address) => connect(&address),
+                None => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} needs an address", arg),
+                )),
+            });
+        }
+
+        None
+    }
+
+    pub fn local_player(&self) -> u8 {
+        match self.role {
+            NetRoles::Host => 1,
+            NetRoles::Join => 2,
+        }
+    }
+
+    pub fn remote_player(&self) -> u8 {
+        3 - self.local_player()
+    }
+
+    pub fn connected(&self) -> bool {
+        self.last_heard
+            .is_some_and(|last_heard| last_heard.elapsed().as_secs_f32() < NET_TIMEOUT)
+    }
+
+    pub fn start_game(&mut self, game: u32, seed: u64) {
+        self.local.game = game;
+        self.local.seed = seed;
+        self.local.commitments.clear();
+        self.local.reveals.clear();
+        self.secrets.clear();
+        self.committed_at = None;
+        self.local.ready = false;
+    }
+
+    pub fn same_game(&self) -> bool {
+        self.remote.game == self.local.game
+    }
+
+    pub fn commit(&mut self, round: u8, choice: Moves) {
+        let secret = Reveal {
+            choice,
+            salt: rand::random(),
+        };
+
+        let commitment = secret.commitment(self.local.game, round, self.local_player());
+
+        self.secrets.push(secret);
+        self.local.commitments.push(commitment);
+        self.committed_at = Some(Instant::now());
+    }
+
+    pub fn remote_committed(&self, round: u8) -> bool {
+        self.same_game() && self.remote.commitments.len() >= round as usize
+    }
+
+    // Publishes the own choice, once both sides are bound to theirs
+    pub fn reveal(&mut self, round: u8) {
+        let index = (round - 1) as usize;
+
+        if !self.remote_committed(round) || self.local.reveals.len() > index {
+            return;
+        }
+
+        if let Some(&secret) = self.secrets.get(index) {
+            self.local.reveals.push(secret);
+        }
+    }
+
+    // `Some(Err(_))` when the reveal doesn't match the commitment
+    pub fn remote_choice(&self, round: u8) -> Option<Result<Moves, Reveal>> {
+        let index = (round - 1) as usize;
+
+        if !self.remote_committed(round) {
+            return None;
+        }
+
+        let reveal = *self.remote.reveals.get(index)?;
+        let commitment = reveal.commitment(self.local.game, round, self.remote_player());
+
+        if commitment == self.remote.commitments[index] {
+            Some(Ok(reveal.choice))
+        } else {
+            Some(Err(reveal))
+        }
+    }
+
+    // Still no valid choice from the other side long after our own commit,
+    // a peer that stays connected can't keep the round open forever
+    pub fn reveal_overdue(&self, round: u8) -> bool {
+        self.secrets.len() >= round as usize
+            && self.remote_choice(round).is_none()
+            && self
+                .committed_at
+                .is_some_and(|committed_at| committed_at.elapsed().as_secs_f32() > REVEAL_TIMEOUT)
+    }
+
+    // Back in the menu while this game is still being played on our side
+    pub fn remote_left(&self) -> bool {
+        !self.connected() || (self.same_game() && self.remote.in_menu)
+    }
+
+    pub fn send_status(&mut self) {
+        let Some(peer) = self.peer else {
+            return;
+        };
+
+        let interval = Duration::from_secs_f32(NET_SEND_INTERVAL);
+
+        if self
+            .last_sent
+            .is_some_and(|last_sent| last_sent.elapsed() < interval)
+        {
+            return;
+        }
+
+        self.last_sent = Some(Instant::now());
+
+        match serde_json::to_vec(&self.local) {
+            Ok(datagram) => {
+                if let Err(error) = self.socket.send_to(&datagram, peer) {
+                    warn!("Could not reach {}: {}", peer, error);
+                }
+            }
+            Err(error) => warn!("Could not encode status: {}", error),
+        }
+    }
+
+    pub fn receive(&mut self) {
+        let mut buffer = [0; 4096];
+
+        while let Ok((length, from)) = self.socket.recv_from(&mut buffer) {
+            // The host plays against whoever reaches it first
+            if self.peer.is_some_and(|peer| peer != from) {
+                continue;
+            }
+
+            match serde_json::from_slice::<PeerStatus>(&buffer[..length]) {
+                Ok(status) => {
+                    self.peer = Some(from);
+                    self.remote = status;
+                    self.last_heard = Some(Instant::now());
+                }
+                Err(error) => warn!("Ignoring datagram from {}: {}", from, error),
+            }
+        }
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    match NetSession::from_args() {
+        Some(Ok(session)) => {
+            info!(
+                "Online as player {} on {}",
+                session.local_player(),
+                session.address
+            );
+            app.insert_resource(session);
+        }
+        Some(Err(error)) => error!("Could not start online session: {}", error),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_in_game() -> NetSession {
+        let mut session = NetSession::host("127.0.0.1:0").unwrap();
+
+        session.start_game(1, 0);
+        session.remote.game = 1;
+
+        session
+    }
+
+    fn remote_commit(session: &mut NetSession, round: u8, reveal: Reveal) {
+        let commitment = reveal.commitment(session.local.game, round, session.remote_player());
+
+        session.remote.commitments.push(commitment);
+    }
+
+    #[test]
+    fn matching_reveal_gives_the_choice() {
+        let mut session = session_in_game();
+        let reveal = Reveal {
+            choice: Moves::Attack,
+            salt: 7,
+        };
+
+        remote_commit(&mut session, 1, reveal);
+        assert_eq!(session.remote_choice(1), None);
+
+        session.remote.reveals.push(reveal);
+        assert_eq!(session.remote_choice(1), Some(Ok(Moves::Attack)));
+    }
+
+    #[test]
+    fn tampered_reveal_is_refused() {
+        let mut session = session_in_game();
+        let reveal = Reveal {
+            choice: Moves::Attack,
+            salt: 7,
+        };
+
+        remote_commit(&mut session, 1, reveal);
+
+        let tampered = Reveal {
+            choice: Moves::Dodge,
+            ..reveal
+        };
+        session.remote.reveals.push(tampered);
+
+        assert_eq!(session.remote_choice(1), Some(Err(tampered)));
+    }
+
+    #[test]
+    fn echoed_commitment_is_refused() {
+        let mut session = session_in_game();
+        session.commit(1, Moves::Attack);
+
+        // The other side sends our own commitment and reveal back
+        session.remote.commitments = session.local.commitments.clone();
+        session.reveal(1);
+        session.remote.reveals = session.local.reveals.clone();
+
+        assert!(matches!(session.remote_choice(1), Some(Err(_))));
+    }
+
+    #[test]
+    fn missing_reveal_is_overdue_after_the_timeout() {
+        let mut session = session_in_game();
+        session.commit(1, Moves::Attack);
+
+        assert!(!session.reveal_overdue(1));
+
+        session.committed_at =
+            Instant::now().checked_sub(Duration::from_secs_f32(REVEAL_TIMEOUT + 1.));
+
+        assert!(session.reveal_overdue(1));
+
+        let reveal = Reveal {
+            choice: Moves::Dodge,
+            salt: 3,
+        };
+        remote_commit(&mut session, 1, reveal);
+        session.remote.reveals.push(reveal);
+
+        assert!(!session.reveal_overdue(1));
+    }
+}
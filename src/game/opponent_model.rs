@@ -0,0 +1,166 @@
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+use super::*;
+
+// Minimum number of observations before a context is trusted for predictions
+const MIN_OBSERVATIONS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Moves {
+    Idle,
+    Attack,
+    Dodge,
+    Buff,
+}
+
+pub const MOVES: [Moves; 4] = [Moves::Idle, Moves::Attack, Moves::Dodge, Moves::Buff];
+
+impl From<PlayerStates> for Moves {
+    fn from(player_state: PlayerStates) -> Self {
+        match player_state {
+            PlayerStates::Idle => Moves::Idle,
+            PlayerStates::Attacking | PlayerStates::NotAttacking => Moves::Attack,
+            PlayerStates::Dodging | PlayerStates::NotDodging => Moves::Dodge,
+            PlayerStates::Buffing => Moves::Buff,
+        }
+    }
+}
+
+impl From<Moves> for PlayerStates {
+    fn from(moves: Moves) -> Self {
+        match moves {
+            Moves::Idle => PlayerStates::Idle,
+            Moves::Attack => PlayerStates::Attacking,
+            Moves::Dodge => PlayerStates::Dodging,
+            Moves::Buff => PlayerStates::Buffing,
+        }
+    }
+}
+
+// What the modeled player knows when choosing, seen from their side
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelContext {
+    pub view: DuelView,
+    pub previous: Option<Moves>,
+}
+
+impl ModelContext {
+    // From the most specific to the most general context
+    fn keys(&self) -> [String; 3] {
+        let me = self.view.me;
+        let opponent = self.view.opponent;
+        let health = match me.health.cmp(&opponent.health) {
+            std::cmp::Ordering::Less => "behind",
+            std::cmp::Ordering::Equal => "even",
+            std::cmp::Ordering::Greater => "ahead",
+        };
+
+        [
+            format!(
+                "round:{}|bullets:{}|dodges:{}|opponent_bullets:{}|opponent_dodges:{}|health:{}|previous:{:?}",
+                self.view.round,
+                me.bullets.min(2),
+                me.dodges.min(1),
+                opponent.bullets.min(1),
+                opponent.dodges.min(1),
+                health,
+                self.previous
+            ),
+            format!(
+                "bullets:{}|opponent_bullets:{}|previous:{:?}",
+                me.bullets.min(1),
+                opponent.bullets.min(1),
+                self.previous
+            ),
+            "any".to_string(),
+        ]
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct OpponentModel {
+    pub counts: HashMap<String, [u32; MOVES.len()]>,
+    #[serde(skip)]
+    pub previous: [Option<Moves>; 2],
+}
+
+impl OpponentModel {
+    pub fn path(profile: u8) -> PathBuf {
+        PathBuf::from(SAVE_DIR)
+            .join("profiles")
+            .join(format!("profile-{}.json", profile + 1))
+    }
+
+    pub fn load(profile: u8) -> Self {
+        fs::read(Self::path(profile))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, profile: u8) -> io::Result<()> {
+        let path = Self::path(profile);
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn observe(&mut self, context: &ModelContext, observed: Moves) {
+        let index = MOVES
+            .iter()
+            .position(|other| *other == observed)
+            .unwrap_or(0);
+
+        for key in context.keys() {
+            self.counts.entry(key).or_default()[index] += 1;
+        }
+    }
+
+    pub fn predict(&self, context: &ModelContext) -> Option<[f32; MOVES.len()]> {
+        context.keys().iter().find_map(|key| {
+            let counts = self.counts.get(key)?;
+            let total: u32 = counts.iter().sum();
+
+            if total < MIN_OBSERVATIONS {
+                return None;
+            }
+
+            Some(counts.map(|count| count as f32 / total as f32))
+        })
+    }
+}
+
+// Picks the action with the best expected outcome against the predicted moves
+pub fn best_response(view: &DuelView, prediction: [f32; MOVES.len()]) -> PlayerStates {
+    let [idle, attack, dodge, buff] = prediction;
+
+    // A predicted shot is only a threat with bullets left
+    let threat = if view.opponent.bullets > 0 {
+        attack
+    } else {
+        0.
+    };
+    let exposed = idle + buff + (attack - threat);
+    let low_health = view.me.health <= DEFAULT_DAMAGE;
+
+    view.available_actions()
+        .into_iter()
+        .map(|action| {
+            let score = match action {
+                PlayerStates::Attacking => {
+                    let trade = if low_health { -0.5 } else { 0.3 };
+                    exposed - 0.5 * dodge + trade * threat
+                }
+                PlayerStates::Dodging => 1.2 * threat - 0.3 * (1. - threat),
+                PlayerStates::Buffing => 0.3 * (1. - threat) - threat,
+                _ => -threat,
+            };
+
+            (action, score)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(PlayerStates::Idle, |(action, _)| action)
+}
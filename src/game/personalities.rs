@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+
+use super::*;
+
+#[derive(Asset, TypePath, Deserialize, Clone)]
+pub struct Personality {
+    pub name: String,
+    pub weights: HashMap<Moves, f32>,
+    // Chance of playing back the opponent's previous move
+    #[serde(default)]
+    pub copy_opponent: f64,
+    pub reaction: [f32; 2],
+    #[serde(default)]
+    pub taunts: HashMap<Moves, Vec<String>>,
+}
+
+impl Personality {
+    // Values the AI would panic on, or misbehave with, get the file skipped
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0. ..=1.).contains(&self.copy_opponent) {
+            return Err(format!(
+                "copy_opponent must be between 0 and 1, got {}",
+                self.copy_opponent
+            ));
+        }
+
+        if let Some(weight) = self
+            .weights
+            .values()
+            .find(|weight| !weight.is_finite() || **weight < 0.)
+        {
+            return Err(format!("weights must be positive numbers, got {}", weight));
+        }
+
+        let [min, max] = self.reaction;
+
+        if !min.is_finite() || !max.is_finite() || min < 0. || max < min {
+            return Err(format!(
+                "reaction must be a range of positive seconds, got [{}, {}]",
+                min, max
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn decide(
+        &self,
+        view: &DuelView,
+        opponent_previous: Option<Moves>,
+        rng: &mut impl Rng,
+    ) -> PlayerStates {
+        let available = view.available_actions();
+
+        if let Some(previous) = opponent_previous {
+            let copied = PlayerStates::from(previous);
+
+            if available.contains(&copied) && rng.random_bool(self.copy_opponent) {
+                return copied;
+            }
+        }
+
+        let weights: Vec<f32> = available
+            .iter()
+            .map(|action| {
+                self.weights
+                    .get(&Moves::from(*action))
+                    .copied()
+                    .unwrap_or(1.)
+                    .max(0.)
+            })
+            .collect();
+
+        let mut roll = rng.random_range(0. ..weights.iter().sum::<f32>().max(f32::EPSILON));
+
+        for (action, weight) in available.iter().zip(weights) {
+            if roll < weight {
+                return *action;
+            }
+            roll -= weight;
+        }
+
+        PlayerStates::Idle
+    }
+
+    // Seconds after the start of betting, kept non-empty for badly written files
+    pub fn reaction_range(&self) -> std::ops::Range<f32> {
+        let [min, max] = self.reaction;
+
+        min..max.max(min + 0.1)
+    }
+
+    pub fn taunt(&self, action: Moves, rng: &mut impl Rng) -> Option<&String> {
+        let taunts = self.taunts.get(&action)?;
+
+        taunts.get(rng.random_range(0..taunts.len().max(1)))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PersonalityLibrary {
+    pub personalities: Vec<Handle<Personality>>,
+}
+
+#[derive(Default)]
+struct PersonalityLoader;
+
+impl AssetLoader for PersonalityLoader {
+    type Asset = Personality;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["personality.json"]
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Personality>();
+    app.init_asset_loader::<PersonalityLoader>();
+    app.init_resource::<PersonalityLibrary>();
+}
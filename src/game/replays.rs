@@ -0,0 +1,86 @@
+// Compact records of played matches: the rules, the seed, where the fighters
+// started and what both players chose each round, with the betting clock
+// reading of the choice. Replaying them through the duel RNG gives the same
+// match back.
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReplayFighter {
+    pub health: u8,
+    pub max_health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+    pub damage: u8,
+    pub luck: u8,
+    pub base_luck: u8,
+    pub marksmanship: u8,
+    pub base_marksmanship: u8,
+    pub buff: Option<Buffes>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReplayChoice {
+    pub choice: Moves,
+    // Seconds into the betting phase, none when decided as it closed
+    pub at: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRound {
+    pub round: u8,
+    pub modifier: Option<RoundModifiers>,
+    pub choices: [ReplayChoice; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub rules: RuleSet,
+    pub seed: u64,
+    pub handicaps: [Handicap; 2],
+    pub first_round: u8,
+    pub fighters: [ReplayFighter; 2],
+    pub rounds: Vec<ReplayRound>,
+}
+
+impl Replay {
+    pub fn directory() -> PathBuf {
+        PathBuf::from(SAVE_DIR).join("replays")
+    }
+
+    pub fn round(&self, round: u8) -> Option<&ReplayRound> {
+        self.rounds.iter().find(|entry| entry.round == round)
+    }
+
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let directory = Self::directory();
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(io::Error::other)?;
+        let path = directory.join(format!("replay-{}.json", recorded_at.as_millis()));
+
+        fs::create_dir_all(&directory)?;
+        fs::write(&path, serde_json::to_vec(self)?)?;
+
+        Ok(path)
+    }
+
+    // File names carry the recording time, the last one sorts last
+    pub fn latest() -> io::Result<Self> {
+        let path = fs::read_dir(Self::directory())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .max()
+            .ok_or(io::ErrorKind::NotFound)?;
+
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use bevy::tasks::Task;
+
 use super::*;
 
 pub mod assets;
@@ -11,12 +15,214 @@ pub struct CountdownTimer(pub Timer);
 #[derive(Resource)]
 pub struct RoundCounter(pub u8);
 
+// How the match ended, once it has
 #[derive(Resource)]
-pub struct GameOver(pub bool);
+pub struct GameOver(pub Option<GameOverEvent>);
 
 #[derive(Resource)]
 pub struct AssetsLoading(pub Vec<UntypedHandle>);
 
+// Data files may fail to load, they are skipped instead of stopping the game
+#[derive(Resource, Default)]
+pub struct DataFilesLoading(pub Vec<UntypedHandle>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Controllers {
+    #[default]
+    Human,
+    Ai(Difficulties),
+    Learner,
+    Personality(usize),
+    External(usize),
+    Remote,
+    Replay,
+}
+
+#[derive(Resource, Default)]
+pub struct MatchSettings {
+    pub quick_draw: bool,
+    pub hidden_choices: bool,
+    pub draft: bool,
+    pub handicaps: [Handicap; 2],
+    pub controllers: [Controllers; 2],
+    pub profile: u8,
+    pub fixed_difficulty: bool,
+    pub win_meter: bool,
+    pub scenario: Option<Handle<Scenario>>,
+    // Shared by both machines in online duels, random otherwise
+    pub seed: Option<u64>,
+}
+
+// Carried across matches, the match offset follows the health margin
+#[derive(Resource, Default)]
+pub struct AdaptiveDifficulty {
+    pub offset: f32,
+    pub match_offset: f32,
+    pub matches: u32,
+    pub human_wins: u32,
+}
+
+impl AdaptiveDifficulty {
+    pub fn level(&self, base: Difficulties) -> f32 {
+        (base.level() + self.offset + self.match_offset).clamp(0., 2.)
+    }
+}
+
+// State at the start of each round, `round` being the round about to be played
+#[derive(Serialize, Clone, Copy)]
+pub struct RoundHistory {
+    pub round: u8,
+    pub health: [u8; 2],
+    pub bullets: [u8; 2],
+    pub dodges: [u8; 2],
+    pub win_chances: Option<WinChances>,
+}
+
+#[derive(Resource, Default, Serialize)]
+pub struct MatchHistory {
+    pub rounds: Vec<RoundHistory>,
+}
+
+// Win chances being simulated off the main thread for the given round
+#[derive(Resource, Default)]
+pub struct WinEstimate {
+    pub round: u8,
+    pub task: Option<Task<WinChances>>,
+}
+
+#[derive(Resource, Default)]
+pub struct MenuCursor(pub usize);
+
+#[derive(Resource, Default)]
+pub struct LobbyInput {
+    pub cursor: usize,
+    pub code: String,
+    pub room: Option<String>,
+}
+
+#[derive(Resource)]
+pub struct DraftTurn(pub u8);
+
+// Key presses of the betting phase and of the too-late window after it, in
+// arrival order, stamped with the betting clock of the frame they came in
+#[derive(Resource, Default)]
+pub struct TimedKeyPresses {
+    pub presses: Vec<TimedKeyPress>,
+    // Game clock when betting opened, the betting clock runs from there
+    pub betting_started_at: Duration,
+}
+
+pub struct TimedKeyPress {
+    pub key: KeyCode,
+    pub at: Duration,
+}
+
+#[derive(Resource)]
+pub struct DrawSignal {
+    pub timer: Timer,
+    pub drawn_at: Option<Duration>,
+}
+
+impl Default for DrawSignal {
+    fn default() -> Self {
+        DrawSignal {
+            timer: Timer::from_seconds(QUICK_DRAW_MIN_DELAY, TimerMode::Once),
+            drawn_at: None,
+        }
+    }
+}
+
+// Picked by the audience for the round being played
+#[derive(Resource, Default)]
+pub struct ActiveModifier(pub Option<RoundModifiers>);
+
+// One generator per player, so both online peers roll the same numbers
+// whatever order their systems visit the players in
+#[derive(Resource)]
+pub struct DuelRng {
+    pub seed: u64,
+    pub players: [StdRng; 2],
+}
+
+impl DuelRng {
+    pub fn seeded(seed: u64) -> Self {
+        DuelRng {
+            seed,
+            players: [1, 2].map(|player| StdRng::seed_from_u64(seed.wrapping_add(player))),
+        }
+    }
+
+    pub fn player(&mut self, player: u8) -> &mut StdRng {
+        &mut self.players[(player - 1) as usize]
+    }
+}
+
+impl DuelDice for DuelRng {
+    fn player_rng(&mut self, player: u8) -> &mut impl Rng {
+        self.player(player)
+    }
+}
+
+impl Default for DuelRng {
+    fn default() -> Self {
+        DuelRng::seeded(rand::random())
+    }
+}
+
+// Demo duel played on the menu while nobody touches the keyboard
+#[derive(Resource)]
+pub struct AttractMode {
+    pub active: bool,
+    pub idle: Timer,
+    pub pause: Timer,
+    pub saved_settings: Option<MatchSettings>,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        AttractMode {
+            active: false,
+            idle: Timer::from_seconds(ATTRACT_IDLE_TIMER, TimerMode::Once),
+            pause: Timer::from_seconds(ATTRACT_PAUSE_TIMER, TimerMode::Once),
+            saved_settings: None,
+        }
+    }
+}
+
+// The replay of the match being played, saved once it is over
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    pub replay: Option<Replay>,
+    pub choice_states: [Option<PlayerStates>; 2],
+    pub choice_times: [Option<f32>; 2],
+}
+
+// Only there while a replay is watched
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub saved_settings: MatchSettings,
+    pub saved_rules: RuleSet,
+    pub applied: [bool; 2],
+    pub speed: usize,
+    pub step: bool,
+    pub pause: Timer,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay, saved_settings: MatchSettings, saved_rules: RuleSet) -> Self {
+        ReplayPlayback {
+            replay,
+            saved_settings,
+            saved_rules,
+            applied: [false; 2],
+            speed: REPLAY_NORMAL_SPEED,
+            step: false,
+            pause: Timer::from_seconds(REPLAY_PAUSE_TIMER, TimerMode::Once),
+        }
+    }
+}
+
 pub(super) fn plugin(app: &mut App) {
     app.insert_resource(BettingTimer(Timer::from_seconds(
         DEFAULT_BETTING_TIMER,
@@ -27,7 +233,23 @@ pub(super) fn plugin(app: &mut App) {
         TimerMode::Once,
     )));
     app.insert_resource(RoundCounter(1));
-    app.insert_resource(GameOver(false));
+    app.insert_resource(GameOver(None));
+    app.init_resource::<MatchSettings>();
+    app.init_resource::<MenuCursor>();
+    app.init_resource::<LobbyInput>();
+    app.insert_resource(DraftTurn(1));
+    app.init_resource::<TimedKeyPresses>();
+    app.init_resource::<DrawSignal>();
+    app.init_resource::<AttractMode>();
+    app.init_resource::<DuelRng>();
+    app.init_resource::<ActiveModifier>();
+    app.init_resource::<ReplayRecorder>();
+    app.init_resource::<MatchTimeline>();
+    app.init_resource::<OpponentModel>();
+    app.init_resource::<AdaptiveDifficulty>();
+    app.init_resource::<RuleSet>();
+    app.init_resource::<MatchHistory>();
+    app.init_resource::<WinEstimate>();
 
     // Audios
     app.insert_resource(assets::MainThemeAudio { ..default() });
@@ -48,4 +270,5 @@ pub(super) fn plugin(app: &mut App) {
     app.insert_resource(assets::BgSprite { ..default() });
 
     app.insert_resource(AssetsLoading(default()));
+    app.init_resource::<DataFilesLoading>();
 }
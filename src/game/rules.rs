@@ -0,0 +1,440 @@
+// Duel rules shared by the game systems and the headless tools
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchResult {
+    Winner(u8),
+    Tie,
+}
+
+impl MatchResult {
+    pub fn winner(&self) -> Option<u8> {
+        match self {
+            MatchResult::Winner(player) => Some(*player),
+            MatchResult::Tie => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameOverCauses {
+    Death,
+    RoundLimit,
+    OutOfAmmo,
+}
+
+// Tunable numbers of a duel, the defaults are the game's
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+    pub luck: u8,
+    pub marksmanship: u8,
+    pub max_round: u8,
+    pub golden_bullet_damage: u8,
+    pub buff_dice_bonus: u8,
+    pub restore_threshold: u8,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            health: DEFAULT_HEALTH,
+            bullets: N_BULLETS,
+            dodges: N_DODGES,
+            luck: DEFAULT_LUCK,
+            marksmanship: DEFAULT_MARKSMANSHIP,
+            max_round: N_MAX_ROUND,
+            golden_bullet_damage: GOLDEN_BULLET_DAMAGE,
+            buff_dice_bonus: BUFF_DICE_BONUS,
+            restore_threshold: RESTORE_THRESHOLD,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn restores(&self, luck: Dice, rng: &mut impl Rng) -> bool {
+        luck.roll(rng) >= self.restore_threshold
+    }
+
+    // Checked at the end of each round: deaths first, then the round limit, then ammo
+    pub fn ending(
+        &self,
+        healths: [u8; 2],
+        bullets: [u8; 2],
+        round: u8,
+    ) -> Option<(MatchResult, GameOverCauses)> {
+        let result = match healths.map(|health| health == 0) {
+            [true, true] => Some(MatchResult::Tie),
+            [true, false] => Some(MatchResult::Winner(2)),
+            [false, true] => Some(MatchResult::Winner(1)),
+            [false, false] => None,
+        };
+
+        if let Some(result) = result {
+            return Some((result, GameOverCauses::Death));
+        }
+
+        if round >= self.max_round {
+            return Some((health_result(healths), GameOverCauses::RoundLimit));
+        }
+
+        if bullets.iter().all(|&bullets| bullets == 0) {
+            return Some((health_result(healths), GameOverCauses::OutOfAmmo));
+        }
+
+        None
+    }
+
+    pub fn match_result(
+        &self,
+        healths: [u8; 2],
+        bullets: [u8; 2],
+        round: u8,
+    ) -> Option<MatchResult> {
+        self.ending(healths, bullets, round)
+            .map(|(result, _)| result)
+    }
+}
+
+// Out of ammo or dodges, the move still shows but does nothing
+pub fn requested_state(requested: PlayerStates, bullets: u8, dodges: u8) -> PlayerStates {
+    match requested {
+        PlayerStates::Attacking if bullets == 0 => PlayerStates::NotAttacking,
+        PlayerStates::Dodging if dodges == 0 => PlayerStates::NotDodging,
+        other => other,
+    }
+}
+
+// Buffs that turn the bet into a shot
+pub fn forced_attack(bullets: u8) -> PlayerStates {
+    requested_state(PlayerStates::Attacking, bullets, 0)
+}
+
+pub fn healed(health: u8, max: u8, amount: u8) -> u8 {
+    health.saturating_add(amount).min(max)
+}
+
+// Both dice of a shot, it hits when marksmanship beats luck
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HitRoll {
+    pub marksmanship: u8,
+    pub luck: u8,
+}
+
+impl HitRoll {
+    pub fn hits(&self) -> bool {
+        self.marksmanship > self.luck
+    }
+}
+
+pub fn roll_hit(marksmanship: Dice, luck: Dice, rng: &mut impl Rng) -> HitRoll {
+    HitRoll {
+        marksmanship: marksmanship.roll(rng),
+        luck: luck.roll(rng),
+    }
+}
+
+pub fn hits(marksmanship: Dice, luck: Dice, rng: &mut impl Rng) -> bool {
+    roll_hit(marksmanship, luck, rng).hits()
+}
+
+pub fn is_restoring_round(round: u8) -> bool {
+    round.is_multiple_of(2)
+}
+
+fn health_result(healths: [u8; 2]) -> MatchResult {
+    match healths[0].cmp(&healths[1]) {
+        std::cmp::Ordering::Less => MatchResult::Winner(2),
+        std::cmp::Ordering::Equal => MatchResult::Tie,
+        std::cmp::Ordering::Greater => MatchResult::Winner(1),
+    }
+}
+
+#[derive(Clone)]
+pub struct Fighter {
+    pub health: u8,
+    pub max_health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+    pub damage: u8,
+    pub luck: Dice,
+    pub base_luck: Dice,
+    pub marksmanship: Dice,
+    pub base_marksmanship: Dice,
+    pub buff: Option<Buffes>,
+    pub state: PlayerStates,
+}
+
+impl Fighter {
+    pub fn new(rules: &RuleSet) -> Self {
+        let luck = Dice { value: rules.luck };
+        let marksmanship = Dice {
+            value: rules.marksmanship,
+        };
+
+        Fighter {
+            health: rules.health,
+            max_health: rules.health,
+            bullets: rules.bullets,
+            dodges: rules.dodges,
+            damage: DEFAULT_DAMAGE,
+            luck,
+            base_luck: luck,
+            marksmanship,
+            base_marksmanship: marksmanship,
+            buff: None,
+            state: PlayerStates::Idle,
+        }
+    }
+
+    pub fn view(&self) -> FighterView {
+        FighterView {
+            health: self.health,
+            bullets: self.bullets,
+            dodges: self.dodges,
+        }
+    }
+
+    fn apply_buff(&mut self, buff: Buffes, rules: &RuleSet) {
+        match buff {
+            Buffes::IncreaseDamageBuff => {
+                self.damage += 1;
+                self.state = forced_attack(self.bullets);
+            }
+            Buffes::GoldenBulletBuff => {
+                self.damage = rules.golden_bullet_damage;
+                self.state = forced_attack(self.bullets);
+            }
+            Buffes::HealBuff => self.health = healed(self.health, self.max_health, 1),
+            Buffes::SuperHealBuff => self.health = healed(self.health, self.max_health, 2),
+            Buffes::LuckBuff => {
+                self.luck += Dice {
+                    value: rules.buff_dice_bonus,
+                }
+            }
+            Buffes::MarksmanshipBuff => {
+                self.marksmanship += Dice {
+                    value: rules.buff_dice_bonus,
+                };
+                self.state = forced_attack(self.bullets);
+            }
+        }
+    }
+}
+
+// What happened during a round, once buffs forced their moves
+#[derive(Debug, Clone, Copy)]
+pub struct RoundReport {
+    pub states: [PlayerStates; 2],
+    pub buffs: [Option<Buffes>; 2],
+    pub damage_taken: [u8; 2],
+}
+
+impl RoundReport {
+    pub fn outcome(&self, player: u8) -> RoundOutcome {
+        let me = (player - 1) as usize;
+        let opponent = 1 - me;
+
+        RoundOutcome {
+            me: Moves::from(self.states[me]),
+            opponent: Moves::from(self.states[opponent]),
+            damage_taken: self.damage_taken[me],
+            damage_dealt: self.damage_taken[opponent],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WinChances {
+    pub players: [f32; 2],
+    pub tie: f32,
+}
+
+// Where a duel rolls its dice: one generator for everything, or one per
+// player like the game, so a seeded duel plays out as the seeded match would
+pub trait DuelDice {
+    fn player_rng(&mut self, player: u8) -> &mut impl Rng;
+}
+
+impl<R: Rng> DuelDice for R {
+    fn player_rng(&mut self, _player: u8) -> &mut impl Rng {
+        self
+    }
+}
+
+// A whole duel without any window, playing the same phases as the game
+#[derive(Clone)]
+pub struct Duel {
+    pub rules: RuleSet,
+    pub round: u8,
+    pub fighters: [Fighter; 2],
+    pub result: Option<MatchResult>,
+    pub ending: Option<GameOverCauses>,
+}
+
+impl Default for Duel {
+    fn default() -> Self {
+        Duel::new(RuleSet::default())
+    }
+}
+
+impl Duel {
+    pub fn new(rules: RuleSet) -> Self {
+        Duel {
+            rules,
+            round: 1,
+            fighters: [Fighter::new(&rules), Fighter::new(&rules)],
+            result: None,
+            ending: None,
+        }
+    }
+
+    pub fn view(&self, player: u8) -> DuelView {
+        let me = (player - 1) as usize;
+
+        DuelView {
+            round: self.round,
+            me: self.fighters[me].view(),
+            opponent: self.fighters[1 - me].view(),
+        }
+    }
+
+    pub fn play_round(
+        &mut self,
+        actions: [PlayerStates; 2],
+        rng: &mut impl DuelDice,
+    ) -> RoundReport {
+        let rules = self.rules;
+        let healths = self.fighters.clone().map(|fighter| fighter.health);
+
+        // Betting
+        for (index, (fighter, action)) in self.fighters.iter_mut().zip(actions).enumerate() {
+            fighter.state = requested_state(action, fighter.bullets, fighter.dodges);
+
+            if fighter.state == PlayerStates::Buffing {
+                fighter.buff = Some(rng.player_rng(index as u8 + 1).random());
+            }
+
+            // A buff set up before the round applies as well, like in the game
+            if let Some(buff) = fighter.buff {
+                fighter.apply_buff(buff, &rules);
+            }
+        }
+
+        // Fighting
+        let attacks = self.fighters.clone().map(|fighter| {
+            (fighter.state == PlayerStates::Attacking)
+                .then_some((fighter.damage, fighter.marksmanship))
+        });
+
+        for fighter in &mut self.fighters {
+            match fighter.state {
+                PlayerStates::Attacking => fighter.bullets = fighter.bullets.saturating_sub(1),
+                PlayerStates::Dodging => fighter.dodges = fighter.dodges.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        for (attacker, attack) in attacks.into_iter().enumerate() {
+            let Some((damage, marksmanship)) = attack else {
+                continue;
+            };
+            let target = &mut self.fighters[1 - attacker];
+            // The game rolls shots with the target's dice
+            let rng = rng.player_rng(2 - attacker as u8);
+
+            if target.state != PlayerStates::Dodging && hits(marksmanship, target.luck, rng) {
+                target.health = target.health.saturating_sub(damage);
+            }
+        }
+
+        let states = self.fighters.clone().map(|fighter| fighter.state);
+        let buffs = self.fighters.clone().map(|fighter| fighter.buff);
+
+        for fighter in &mut self.fighters {
+            fighter.buff = None;
+
+            if fighter.state == PlayerStates::Attacking {
+                fighter.damage = DEFAULT_DAMAGE;
+            }
+
+            if fighter.state != PlayerStates::Buffing {
+                fighter.luck = fighter.base_luck;
+                fighter.marksmanship = fighter.base_marksmanship;
+            }
+        }
+
+        // Rounding up
+        let report = RoundReport {
+            states,
+            buffs,
+            damage_taken: [0, 1]
+                .map(|index| healths[index].saturating_sub(self.fighters[index].health)),
+        };
+
+        let ending = rules.ending(
+            self.fighters.clone().map(|fighter| fighter.health),
+            self.fighters.clone().map(|fighter| fighter.bullets),
+            self.round,
+        );
+
+        self.result = ending.map(|(result, _)| result);
+        self.ending = ending.map(|(_, cause)| cause);
+
+        if self.result.is_some() {
+            return report;
+        }
+
+        if is_restoring_round(self.round) {
+            for (index, fighter) in self.fighters.iter_mut().enumerate() {
+                if rules.restores(fighter.luck, rng.player_rng(index as u8 + 1)) {
+                    fighter.bullets += 1;
+                }
+            }
+
+            for (index, fighter) in self.fighters.iter_mut().enumerate() {
+                if rules.restores(fighter.luck, rng.player_rng(index as u8 + 1)) {
+                    fighter.dodges += 1;
+                }
+            }
+        }
+
+        for fighter in &mut self.fighters {
+            fighter.state = PlayerStates::Idle;
+        }
+
+        self.round += 1;
+
+        report
+    }
+
+    // Plays the rest of the duel over and over, both sides following the normal bot
+    pub fn win_chances(&self, samples: u32, rng: &mut impl Rng) -> WinChances {
+        let mut counts = [0; 3];
+
+        for _ in 0..samples {
+            let mut duel = self.clone();
+
+            while duel.result.is_none() {
+                let actions =
+                    [1, 2].map(|player| ai::decide(Difficulties::Normal, &duel.view(player), rng));
+
+                duel.play_round(actions, rng);
+            }
+
+            match duel.result {
+                Some(MatchResult::Winner(player)) => counts[(player - 1) as usize] += 1,
+                _ => counts[2] += 1,
+            }
+        }
+
+        let samples = samples.max(1) as f32;
+
+        WinChances {
+            players: [counts[0] as f32 / samples, counts[1] as f32 / samples],
+            tie: counts[2] as f32 / samples,
+        }
+    }
+}
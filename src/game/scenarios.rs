@@ -0,0 +1,139 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+
+use super::*;
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct ScenarioPlayer {
+    pub health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+    pub buff: Option<Buffes>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    // Seeds the match, so the dice roll the same every attempt
+    pub seed: u64,
+    pub round: u8,
+    pub players: [ScenarioPlayer; 2],
+    // Player 2 plays one action per round, starting at `round`
+    pub opponent: Vec<PlayerStates>,
+    // A winning line for player 1, checked when the scenario is loaded
+    pub solution: Vec<PlayerStates>,
+}
+
+impl Scenario {
+    pub fn validate(&self, rules: &RuleSet) -> Result<(), String> {
+        if !(1..=rules.max_round).contains(&self.round) {
+            return Err(format!(
+                "round must be between 1 and {}, got {}",
+                rules.max_round, self.round
+            ));
+        }
+
+        if self.players.iter().any(|player| player.health == 0) {
+            return Err("both players need some health".into());
+        }
+
+        if self.play(rules).and_then(|result| result.winner()) != Some(1) {
+            return Err(format!("the solution doesn't win with seed {}", self.seed));
+        }
+
+        Ok(())
+    }
+
+    // The solution against the opponent, rolled like the seeded match
+    fn play(&self, rules: &RuleSet) -> Option<MatchResult> {
+        let mut duel = Duel::new(*rules);
+        let mut rng = DuelRng::seeded(self.seed);
+        let scripts = [&self.solution, &self.opponent].map(|actions| ScriptedActions {
+            first_round: self.round,
+            actions: actions.clone(),
+        });
+
+        duel.round = self.round;
+
+        // Same setup as the game
+        for (fighter, setup) in duel.fighters.iter_mut().zip(self.players) {
+            fighter.health = setup.health;
+            fighter.max_health = setup.health.max(DEFAULT_HEALTH);
+            fighter.bullets = setup.bullets;
+            fighter.dodges = setup.dodges;
+            fighter.buff = setup.buff;
+        }
+
+        while duel.result.is_none() {
+            let round = duel.round;
+            duel.play_round(
+                scripts.each_ref().map(|script| script.action(round)),
+                &mut rng,
+            );
+        }
+
+        duel.result
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ScenarioLibrary {
+    pub scenarios: Vec<Handle<Scenario>>,
+}
+
+#[derive(Default)]
+struct ScenarioLoader;
+
+impl AssetLoader for ScenarioLoader {
+    type Asset = Scenario;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scenario.json"]
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Scenario>();
+    app.init_asset_loader::<ScenarioLoader>();
+    app.init_resource::<ScenarioLibrary>();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn shipped_scenarios_are_solved_with_their_seed() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(ASSETS_DIR)
+            .join("scenarios");
+
+        for entry in fs::read_dir(folder).unwrap() {
+            let path = entry.unwrap().path();
+            let scenario: Scenario = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+
+            assert_eq!(
+                scenario.validate(&RuleSet::default()),
+                Ok(()),
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
@@ -14,5 +14,44 @@ pub const DEFAULT_LUCK: u8 = 50;
 pub const N_MAX_ROUND: u8 = 6;
 pub const DEFAULT_DAMAGE: u8 = 1;
 pub const DEFAULT_HEALTH: u8 = 3;
+pub const N_BAR_SEGMENTS: u8 = 3;
 pub const DEFAULT_COUNTDOWN_TIMER: f32 = 3.0;
 pub const DEFAULT_BETTING_TIMER: f32 = 5.0;
+pub const TOO_LATE_WINDOW: f32 = 0.3;
+pub const GOLDEN_BULLET_DAMAGE: u8 = 5;
+pub const BUFF_DICE_BONUS: u8 = 50;
+pub const RESTORE_THRESHOLD: u8 = 25;
+pub const WIN_ESTIMATE_SAMPLES: u32 = 2000;
+pub const N_DRAFT_PICKS: u8 = 4;
+pub const DRAFT_DICE_BONUS: u8 = 10;
+pub const N_MAX_HANDICAP: u8 = 3;
+pub const HANDICAP_LUCK_BONUS: u8 = 10;
+pub const HANDICAP_BETTING_BONUS: f32 = 1.0;
+pub const N_PROFILES: u8 = 4;
+pub const SAVE_DIR: &str = "saves";
+pub const ASSETS_DIR: &str = "assets";
+pub const QUICK_DRAW_MIN_DELAY: f32 = 1.0;
+pub const QUICK_DRAW_MAX_DELAY: f32 = 3.5;
+pub const ADAPTIVE_MATCH_STEP: f32 = 0.25;
+pub const ADAPTIVE_MARGIN_WEIGHT: f32 = 0.5;
+pub const ADAPTIVE_MAX_OFFSET: f32 = 2.0;
+pub const ATTRACT_IDLE_TIMER: f32 = 20.0;
+pub const ATTRACT_PAUSE_TIMER: f32 = 1.5;
+pub const ATTRACT_GAME_OVER_TIMER: f32 = 5.0;
+pub const NET_SEND_INTERVAL: f32 = 0.1;
+pub const NET_TIMEOUT: f32 = 5.0;
+pub const REVEAL_TIMEOUT: f32 = 10.0;
+pub const LOBBY_PORT: u16 = 7778;
+pub const LOBBY_ANNOUNCE_INTERVAL: f32 = 1.0;
+pub const LOBBY_EXPIRY: f32 = 3.0;
+pub const ROOM_CODE_LENGTH: usize = 4;
+pub const SPECTATOR_SNAPSHOT_INTERVAL: f32 = 0.25;
+pub const SPECTATOR_BUFFER: usize = 64;
+pub const AUDIENCE_OPTIONS: usize = 2;
+pub const AUDIENCE_TIMEOUT: f32 = 5.0;
+pub const AUDIENCE_MAX_LINE: usize = 2048;
+pub const AUDIENCE_MAX_HEADERS: usize = 64;
+pub const REPLAY_SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+pub const REPLAY_NORMAL_SPEED: usize = 2;
+pub const REPLAY_PAUSE_TIMER: f32 = 1.5;
+pub const DEDICATED_SERVER_ADDRESS: &str = "0.0.0.0:7780";
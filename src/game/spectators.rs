@@ -0,0 +1,227 @@
+// Read-only stream of the match for dashboards and big screens.
+// Every connected TCP client receives one JSON `SpectatorMessage` per line:
+// regular snapshots of the HUD plus the fight events as they happen.
+//
+//     hit_a_key_game --spectators 0.0.0.0:7779
+//     hit_a_key_game --watch 127.0.0.1:7779
+//     cargo run --bin spectator -- 127.0.0.1:7779
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, SyncSender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub player: u8,
+    pub health: u8,
+    pub max_health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+    pub state: PlayerStates,
+    pub buff: Option<Buffes>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchSnapshot {
+    pub round: u8,
+    pub max_round: u8,
+    pub play_state: PlayStates,
+    pub betting_time_left: f32,
+    pub players: Vec<PlayerSnapshot>,
+    pub win_chances: Option<WinChances>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpectatorMessage {
+    Snapshot(MatchSnapshot),
+    PlayState {
+        state: PlayStates,
+    },
+    Attack {
+        player: u8,
+        attacker: u8,
+        damage: u8,
+    },
+    Damage {
+        player: u8,
+        attacker: u8,
+        value: u8,
+    },
+    Missed {
+        player: u8,
+    },
+    Dodged {
+        player: u8,
+    },
+    GameOver {
+        winner: Option<u8>,
+    },
+}
+
+#[derive(Resource)]
+pub struct SpectatorServer {
+    pub address: SocketAddr,
+    pub snapshot_timer: Timer,
+    // One writer thread per client, a slow one never holds the game back
+    clients: Arc<Mutex<Vec<SyncSender<String>>>>,
+}
+
+impl SpectatorServer {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let clients: Arc<Mutex<Vec<SyncSender<String>>>> = Arc::default();
+        let accepted = clients.clone();
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let (sender, receiver) = mpsc::sync_channel::<String>(SPECTATOR_BUFFER);
+
+                if let Ok(mut clients) = accepted.lock() {
+                    clients.push(sender);
+                }
+
+                thread::spawn(move || {
+                    for line in receiver {
+                        if writeln!(stream, "{}", line).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(SpectatorServer {
+            address,
+            snapshot_timer: Timer::from_seconds(SPECTATOR_SNAPSHOT_INTERVAL, TimerMode::Repeating),
+            clients,
+        })
+    }
+
+    // `--spectators ADDRESS` on the command line
+    pub fn from_args() -> Option<io::Result<Self>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--spectators" {
+                return Some(match args.next() {
+                    Some(address) => SpectatorServer::bind(&address),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--spectators needs an address",
+                    )),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn broadcast(&self, message: &SpectatorMessage) {
+        let line = match serde_json::to_string(message) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!("Could not encode spectator message: {}", error);
+                return;
+            }
+        };
+
+        // Clients whose writer thread stopped, or fell too far behind, are
+        // dropped here
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| client.try_send(line.clone()).is_ok());
+        }
+    }
+}
+
+// A second game instance following the stream of another one
+#[derive(Resource)]
+pub struct SpectatorClient {
+    pub address: String,
+    messages: Mutex<Receiver<SpectatorMessage>>,
+}
+
+impl SpectatorClient {
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let reader = BufReader::new(TcpStream::connect(address)?);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                let Ok(message) = serde_json::from_str::<SpectatorMessage>(&line) else {
+                    continue;
+                };
+
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(SpectatorClient {
+            address: address.to_string(),
+            messages: Mutex::new(receiver),
+        })
+    }
+
+    // `--watch ADDRESS` on the command line
+    pub fn from_args() -> Option<io::Result<Self>> {
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--watch" {
+                return Some(match args.next() {
+                    Some(address) => SpectatorClient::connect(&address),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--watch needs an address",
+                    )),
+                });
+            }
+        }
+
+        None
+    }
+
+    // Messages received since the previous call, `None` once the stream closed
+    pub fn receive(&self) -> Option<Vec<SpectatorMessage>> {
+        let messages = self.messages.lock().ok()?;
+        let mut received = vec![];
+
+        loop {
+            match messages.try_recv() {
+                Ok(message) => received.push(message),
+                Err(TryRecvError::Empty) => return Some(received),
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    match SpectatorServer::from_args() {
+        Some(Ok(server)) => {
+            info!("Streaming matches to spectators on {}", server.address);
+            app.insert_resource(server);
+        }
+        Some(Err(error)) => error!("Could not start spectator stream: {}", error),
+        None => {}
+    }
+
+    match SpectatorClient::from_args() {
+        Some(Ok(client)) => {
+            info!("Watching the match streamed on {}", client.address);
+            app.insert_resource(client);
+        }
+        Some(Err(error)) => error!("Could not watch the stream: {}", error),
+        None => {}
+    }
+}
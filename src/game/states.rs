@@ -1,9 +1,10 @@
 use super::*;
 
-#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum PlayStates {
     #[default]
     Paused,
+    Drafting,
     Preparing,
     Countdown,
     Betting,
@@ -15,6 +16,7 @@ pub enum PlayStates {
 impl PlayStates {
     pub fn next(&self) -> Self {
         match *self {
+            PlayStates::Drafting => PlayStates::Countdown,
             PlayStates::Preparing => PlayStates::Countdown,
             PlayStates::Countdown => PlayStates::Betting,
             PlayStates::Betting => PlayStates::Fighting,
@@ -30,7 +32,10 @@ pub enum AppStates {
     #[default]
     Loading,
     Menu,
+    Lobby,
     InGame,
+    // Following a match played by another instance
+    Watching,
 }
 
 pub(super) fn plugin(app: &mut App) {
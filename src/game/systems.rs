@@ -1,8 +1,15 @@
 use super::*;
 
+pub mod attract_mode;
 pub mod in_game;
+pub mod joining;
 pub mod loading;
+pub mod lobby;
 pub mod menu;
+pub mod online;
+pub mod resolving;
+pub mod spectating;
+pub mod watching;
 
 // Global systems go here
 
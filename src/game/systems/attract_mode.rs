@@ -0,0 +1,167 @@
+// ATTRACT MODE
+use std::time::Duration;
+
+use super::*;
+
+const DEMO_DIFFICULTIES: [Difficulties; 3] =
+    [Difficulties::Easy, Difficulties::Normal, Difficulties::Hard];
+
+fn attract_active(attract_mode: Res<AttractMode>) -> bool {
+    attract_mode.active
+}
+
+fn reset_idle_timer(mut attract_mode: ResMut<AttractMode>) {
+    attract_mode.idle.reset();
+}
+
+fn start_demo_when_idle(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut settings: ResMut<MatchSettings>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if keys.get_just_pressed().next().is_some() {
+        attract_mode.idle.reset();
+        return;
+    }
+
+    attract_mode.idle.tick(time.delta());
+
+    if !attract_mode.idle.just_finished() {
+        return;
+    }
+
+    // Plain duel between two bots, the player's settings come back afterwards
+    let mut rng = rand::rng();
+    let demo_settings = MatchSettings {
+        controllers: [0, 1].map(|_| {
+            Controllers::Ai(DEMO_DIFFICULTIES[rng.random_range(0..DEMO_DIFFICULTIES.len())])
+        }),
+        fixed_difficulty: true,
+        ..default()
+    };
+
+    attract_mode.saved_settings = Some(std::mem::replace(&mut *settings, demo_settings));
+    attract_mode.active = true;
+    next_app_state.set(AppStates::InGame);
+}
+
+fn spawn_demo_text(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new("Demo - press any key"),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::srgba(1., 1., 1., 0.75)),
+        GlobalZIndex(3),
+        DemoText,
+        InGameEntity,
+    ));
+}
+
+fn leave_demo_on_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if keys.get_just_pressed().next().is_some() {
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+fn reset_pause_timer(mut attract_mode: ResMut<AttractMode>) {
+    attract_mode.pause.reset();
+}
+
+// Nobody is there to press space between rounds
+fn skip_preparing(
+    time: Res<Time>,
+    mut attract_mode: ResMut<AttractMode>,
+    play_state: Res<State<PlayStates>>,
+    mut next_play_state: ResMut<NextState<PlayStates>>,
+) {
+    attract_mode.pause.tick(time.delta());
+
+    if attract_mode.pause.just_finished() {
+        next_play_state.set(play_state.get().next());
+    }
+}
+
+fn reset_game_over_timer(mut attract_mode: ResMut<AttractMode>) {
+    attract_mode
+        .pause
+        .set_duration(Duration::from_secs_f32(ATTRACT_GAME_OVER_TIMER));
+    attract_mode.pause.reset();
+}
+
+fn end_demo_after_game_over(
+    time: Res<Time>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    attract_mode.pause.tick(time.delta());
+
+    if attract_mode.pause.just_finished() {
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+fn restore_settings(mut attract_mode: ResMut<AttractMode>, mut settings: ResMut<MatchSettings>) {
+    if let Some(saved_settings) = attract_mode.saved_settings.take() {
+        *settings = saved_settings;
+    }
+
+    attract_mode.active = false;
+    attract_mode
+        .pause
+        .set_duration(Duration::from_secs_f32(ATTRACT_PAUSE_TIMER));
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::Menu), reset_idle_timer);
+
+    app.add_systems(
+        Update,
+        start_demo_when_idle
+            .run_if(in_state(AppStates::Menu))
+            .run_if(not(resource_exists::<NetSession>)),
+    );
+
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        spawn_demo_text.run_if(attract_active),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Preparing),
+        reset_pause_timer.run_if(attract_active),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::GameOver),
+        reset_game_over_timer.run_if(attract_active),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            leave_demo_on_input,
+            skip_preparing.run_if(in_state(PlayStates::Preparing)),
+            end_demo_after_game_over.run_if(in_state(PlayStates::GameOver)),
+        )
+            .run_if(attract_active)
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(
+        OnExit(AppStates::InGame),
+        restore_settings.run_if(attract_active),
+    );
+}
@@ -1,24 +1,45 @@
 use super::*;
 
+pub mod adaptive_difficulty;
+pub mod ai_players;
+pub mod audience_voting;
 pub mod betting;
 pub mod countdown;
+pub mod drafting;
+pub mod external_players;
 pub mod fighting;
 pub mod game_over;
+pub mod hidden_choices;
+pub mod match_history;
 pub mod paused;
 pub mod preparing;
+pub mod quick_draw;
+pub mod replay_recording;
+pub mod replay_viewer;
 pub mod rounding_up;
+pub mod scenario;
+pub mod timeline;
+pub mod win_meter;
 
 fn reset_game(mut rounds: ResMut<RoundCounter>, mut game_over: ResMut<GameOver>) {
     rounds.0 = 1; // reset rounds
-    game_over.0 = false; // reset game_over
+    game_over.0 = None; // reset game_over
 }
 
 fn pause_game(mut next_play_state: ResMut<NextState<PlayStates>>) {
     next_play_state.set(PlayStates::Paused);
 }
 
-fn launch_game(mut next_play_state: ResMut<NextState<PlayStates>>) {
-    next_play_state.set(PlayStates::Countdown);
+fn seed_duel_rng(settings: Res<MatchSettings>, mut duel_rng: ResMut<DuelRng>) {
+    *duel_rng = DuelRng::seeded(settings.seed.unwrap_or_else(rand::random));
+}
+
+fn launch_game(settings: Res<MatchSettings>, mut next_play_state: ResMut<NextState<PlayStates>>) {
+    if settings.draft && settings.scenario.is_none() {
+        next_play_state.set(PlayStates::Drafting);
+    } else {
+        next_play_state.set(PlayStates::Countdown);
+    }
 }
 
 fn spawn_main_theme(mut commands: Commands, main_theme: Res<assets::MainThemeAudio>) {
@@ -34,7 +55,11 @@ fn spawn_main_theme(mut commands: Commands, main_theme: Res<assets::MainThemeAud
     }
 }
 
-fn spawn_bg(mut commands: Commands, bg_texture: Res<assets::BgSprite>, window: Single<&Window>) {
+pub(super) fn spawn_bg(
+    mut commands: Commands,
+    bg_texture: Res<assets::BgSprite>,
+    window: Single<&Window>,
+) {
     if let Some(texture) = bg_texture.sprite.as_ref() {
         commands.spawn((
             Sprite {
@@ -96,7 +121,10 @@ fn listen_spawn_player_tick_ui(
     }
 }
 
-fn despawn_player_tick_ui(mut commands: Commands, query: Query<Entity, With<PlayerTickText>>) {
+pub(super) fn despawn_player_tick_ui(
+    mut commands: Commands,
+    query: Query<Entity, With<PlayerTickText>>,
+) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
@@ -154,20 +182,81 @@ fn despawn_timer_ui(mut commands: Commands, query: Query<Entity, With<TimerUITex
     }
 }
 
+fn duel_view(
+    query: &Query<(&Health, &Bullets, &Dodges, &Player)>,
+    player: u8,
+    round: u8,
+) -> Option<DuelView> {
+    let mut me = None;
+    let mut opponent = None;
+
+    for (health, bullets, dodges, other) in query {
+        let view = FighterView {
+            health: health.value,
+            bullets: bullets.value,
+            dodges: dodges.value,
+        };
+
+        if other.value == player {
+            me = Some(view);
+        } else {
+            opponent = Some(view);
+        }
+    }
+
+    Some(DuelView {
+        round,
+        me: me?,
+        opponent: opponent?,
+    })
+}
+
+fn insert_controller(
+    player: &mut EntityCommands,
+    controller: Controllers,
+    keys: [KeyCode; N_KEYS_PER_PLAYER],
+    personalities: &PersonalityLibrary,
+    external_bots: &ExternalBotLibrary,
+) {
+    match controller {
+        Controllers::Human => player.insert(KeyAssignment(keys)),
+        Controllers::Ai(difficulty) => player.insert(AiController::new(difficulty)),
+        Controllers::Learner => player.insert(AiController::learner()),
+        Controllers::Personality(index) => match personalities.personalities.get(index) {
+            Some(personality) => player.insert(AiController::with_personality(personality.clone())),
+            None => player.insert(AiController::new(Difficulties::Normal)),
+        },
+        Controllers::External(index) => match external_bots.bots.get(index) {
+            Some(config) => player.insert(ExternalBot::new(config.clone())),
+            None => player.insert(AiController::new(Difficulties::Normal)),
+        },
+        Controllers::Remote => player.insert(RemotePlayer),
+        Controllers::Replay => player.insert(ReplayedPlayer),
+    };
+}
+
 fn spawn_players(
     mut commands: Commands,
     hand_texture: Res<assets::HandSpritesheet>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    settings: Res<MatchSettings>,
+    personalities: Res<PersonalityLibrary>,
+    external_bots: Res<ExternalBotLibrary>,
 ) {
+    let [handicap_one, handicap_two] = settings.handicaps;
+
     if let Some(texture) = hand_texture.spritesheet.as_ref() {
         let layout = TextureAtlasLayout::from_grid(UVec2::splat(150), 4, 1, None, None);
         let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
-        commands.spawn((
+        let mut player_one = commands.spawn((
             Player { value: 1 },
             Target { value: 2 },
-            KeyAssignment(PLAYER_ONE_KEYS),
             PlayerState(PlayerStates::Idle),
+            handicap_one,
+            handicap_one.health(),
+            handicap_one.bullets(),
+            handicap_one.luck(),
             Sprite {
                 image: texture.clone(),
                 texture_atlas: Some(TextureAtlas {
@@ -180,12 +269,22 @@ fn spawn_players(
             Transform::from_xyz(-350., 0.0, 0.0),
             InGameEntity,
         ));
-
-        commands.spawn((
+        insert_controller(
+            &mut player_one,
+            settings.controllers[0],
+            PLAYER_ONE_KEYS,
+            &personalities,
+            &external_bots,
+        );
+
+        let mut player_two = commands.spawn((
             Player { value: 2 },
             Target { value: 1 },
-            KeyAssignment(PLAYER_TWO_KEYS),
             PlayerState(PlayerStates::Idle),
+            handicap_two,
+            handicap_two.health(),
+            handicap_two.bullets(),
+            handicap_two.luck(),
             Sprite {
                 image: texture.clone(),
                 texture_atlas: Some(TextureAtlas {
@@ -199,10 +298,56 @@ fn spawn_players(
             Transform::from_xyz(350., 0.0, 0.0),
             InGameEntity,
         ));
+        insert_controller(
+            &mut player_two,
+            settings.controllers[1],
+            PLAYER_TWO_KEYS,
+            &personalities,
+            &external_bots,
+        );
     }
 }
 
-fn spawn_play_state_text(mut commands: Commands, query: Query<&Window>) {
+fn spawn_handicap_text(
+    mut commands: Commands,
+    query: Query<(&Handicap, &Transform), With<Player>>,
+    window: Single<&Window>,
+) {
+    let dimensions = [300., 25.];
+
+    for (handicap, transform) in &query {
+        if !handicap.is_active() {
+            continue;
+        }
+
+        commands.spawn((
+            Node {
+                width: Val::Px(dimensions[0]),
+                height: Val::Px(dimensions[1]),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(160.),
+                left: Val::Px(
+                    transform.translation.x * 1.5 + window.width() / 2. - (dimensions[0] / 2.),
+                ),
+                ..default()
+            },
+            Text::new(format!(
+                "+{} HP  +{} ammo  +{} luck  +{}s",
+                handicap.health, handicap.bullets, handicap.luck, handicap.betting_time
+            )),
+            TextFont {
+                font_size: 15.,
+                ..default()
+            },
+            TextColor(Color::srgba(1., 1., 1., 0.5)),
+            TextLayout::new_with_justify(JustifyText::Center),
+            HandicapText,
+            InGameEntity,
+        ));
+    }
+}
+
+pub(super) fn spawn_play_state_text(mut commands: Commands, query: Query<&Window>) {
     let window = query.single();
     let dimensions = [250., 200.];
 
@@ -236,7 +381,7 @@ fn play_state_text_update(
     }
 }
 
-fn spawn_round_number_text(mut commands: Commands, query: Query<&Window>) {
+pub(super) fn spawn_round_number_text(mut commands: Commands, query: Query<&Window>) {
     let window = query.single();
     let dimensions = [250., 100.];
 
@@ -302,7 +447,7 @@ fn player_state_audio_read(
     }
 }
 
-fn spawn_health_bar(
+pub(super) fn spawn_health_bar(
     mut commands: Commands,
     query: Query<(&Player, &Transform), With<Player>>,
     texture: Res<assets::HealthSpritesheet>,
@@ -339,6 +484,19 @@ fn spawn_health_bar(
     }
 }
 
+// Atlas index of a bar scaled to `full`, 0 being a full bar and
+// N_BAR_SEGMENTS an empty one. Anything left keeps at least one segment
+fn bar_index(value: u8, full: u8) -> usize {
+    let full = full.max(1) as usize;
+    let value = (value as usize).min(full);
+    let segments = match value {
+        0 => 0,
+        _ => ((value * N_BAR_SEGMENTS as usize + full / 2) / full).max(1),
+    };
+
+    N_BAR_SEGMENTS as usize - segments
+}
+
 fn health_bar_update(
     mut query_ui: Query<(&HealthBar, &mut Sprite)>,
     query_state: Query<(&Health, &Player), With<Player>>,
@@ -347,14 +505,14 @@ fn health_bar_update(
         for (health, player) in &query_state {
             if health_bar.value == player.value {
                 if let Some(atlas) = &mut sprite.texture_atlas {
-                    atlas.index = 3_u8.checked_sub(health.value).unwrap_or(0) as usize;
+                    atlas.index = bar_index(health.value, health.max);
                 }
             }
         }
     }
 }
 
-fn spawn_mana_bar(
+pub(super) fn spawn_mana_bar(
     mut commands: Commands,
     query: Query<(&Player, &Transform), With<Player>>,
     texture: Res<assets::ManaSpritesheet>,
@@ -386,6 +544,7 @@ fn spawn_mana_bar(
                 ),
                 ManaBar {
                     value: player.value,
+                    full: N_BAR_SEGMENTS,
                 },
                 InGameEntity,
             ));
@@ -394,21 +553,23 @@ fn spawn_mana_bar(
 }
 
 fn mana_bar_update(
-    mut query_ui: Query<(&ManaBar, &mut Sprite)>,
+    mut query_ui: Query<(&mut ManaBar, &mut Sprite)>,
     query_state: Query<(&Bullets, &Player), With<Player>>,
 ) {
-    for (mana_bar, mut sprite) in &mut query_ui {
+    for (mut mana_bar, mut sprite) in &mut query_ui {
         for (bullets, player) in &query_state {
             if mana_bar.value == player.value {
+                mana_bar.full = mana_bar.full.max(bullets.value);
+
                 if let Some(atlas) = &mut sprite.texture_atlas {
-                    atlas.index = 3_u8.checked_sub(bullets.value).unwrap_or(0) as usize;
+                    atlas.index = bar_index(bullets.value, mana_bar.full);
                 }
             }
         }
     }
 }
 
-fn spawn_stamina_bar(
+pub(super) fn spawn_stamina_bar(
     mut commands: Commands,
     query: Query<(&Player, &Transform), With<Player>>,
     texture: Res<assets::StaminaSpritesheet>,
@@ -440,6 +601,7 @@ fn spawn_stamina_bar(
                 ),
                 StaminaBar {
                     value: player.value,
+                    full: N_BAR_SEGMENTS,
                 },
                 InGameEntity,
             ));
@@ -448,14 +610,16 @@ fn spawn_stamina_bar(
 }
 
 fn stamina_bar_update(
-    mut query_ui: Query<(&StaminaBar, &mut Sprite)>,
+    mut query_ui: Query<(&mut StaminaBar, &mut Sprite)>,
     query_state: Query<(&Dodges, &Player), With<Player>>,
 ) {
-    for (mana_bar, mut sprite) in &mut query_ui {
+    for (mut stamina_bar, mut sprite) in &mut query_ui {
         for (dodges, player) in &query_state {
-            if mana_bar.value == player.value {
+            if stamina_bar.value == player.value {
+                stamina_bar.full = stamina_bar.full.max(dodges.value);
+
                 if let Some(atlas) = &mut sprite.texture_atlas {
-                    atlas.index = 3_u8.checked_sub(dodges.value).unwrap_or(0) as usize;
+                    atlas.index = bar_index(dodges.value, stamina_bar.full);
                 }
             }
         }
@@ -523,7 +687,7 @@ fn listen_spawn_alert_text(
     }
 }
 
-fn despawn_alert_text(mut commands: Commands, query: Query<Entity, With<AlertText>>) {
+pub(super) fn despawn_alert_text(mut commands: Commands, query: Query<Entity, With<AlertText>>) {
     for entity in &query {
         commands.entity(entity).despawn();
     }
@@ -534,6 +698,7 @@ pub fn plugin(app: &mut App) {
         OnEnter(AppStates::InGame),
         (
             spawn_main_theme,
+            seed_duel_rng,
             spawn_bg,
             spawn_players,
             (
@@ -542,6 +707,7 @@ pub fn plugin(app: &mut App) {
                 spawn_health_bar,
                 spawn_mana_bar,
                 spawn_stamina_bar,
+                spawn_handicap_text,
             ),
             launch_game,
         )
@@ -557,19 +723,27 @@ pub fn plugin(app: &mut App) {
         Update,
         (
             audio_react_to_input,
-            player_state_audio_read,
+            player_state_audio_read.run_if(hidden_choices::choices_revealed),
             listen_game_overs,
+            round_number_text_update,
+            play_state_text_update,
+        )
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    // Also drawing matches followed from another instance
+    app.add_systems(
+        Update,
+        (
             listen_spawn_alert_text,
             listen_spawn_player_tick_ui,
             animate_player_tick_text_opacity,
             animate_player_tick_font_size,
-            round_number_text_update,
-            play_state_text_update,
-            player_state_hand_texture_update,
+            player_state_hand_texture_update.run_if(hidden_choices::choices_revealed),
             health_bar_update,
             mana_bar_update,
             stamina_bar_update,
         )
-            .run_if(in_state(AppStates::InGame)),
+            .run_if(in_state(AppStates::InGame).or(in_state(AppStates::Watching))),
     );
 }
@@ -0,0 +1,142 @@
+// ADAPTIVE DIFFICULTY
+use super::*;
+
+// Only single-player matches against a plain difficulty bot adapt
+fn adaptive_enabled(
+    settings: Res<MatchSettings>,
+    query_humans: Query<(), With<KeyAssignment>>,
+    query_bots: Query<&AiController>,
+) -> bool {
+    !settings.fixed_difficulty
+        && settings.scenario.is_none()
+        && query_humans.iter().count() == 1
+        && query_bots
+            .iter()
+            .any(|ai_controller| !ai_controller.learning && ai_controller.personality.is_none())
+}
+
+fn adaptive_playing(query: Query<&AiController>) -> bool {
+    query.iter().any(|ai_controller| ai_controller.adaptive)
+}
+
+fn enable_adaptive_difficulty(
+    mut query: Query<&mut AiController>,
+    mut adaptive_difficulty: ResMut<AdaptiveDifficulty>,
+) {
+    adaptive_difficulty.match_offset = 0.;
+
+    for mut ai_controller in &mut query {
+        ai_controller.adaptive = !ai_controller.learning && ai_controller.personality.is_none();
+    }
+}
+
+// Leading on health makes the bot sharper for the rest of the match, trailing softens it
+fn follow_health_margin(
+    mut adaptive_difficulty: ResMut<AdaptiveDifficulty>,
+    query_humans: Query<&Health, With<KeyAssignment>>,
+    query_bots: Query<&Health, With<AiController>>,
+) {
+    let (Ok(human), Ok(bot)) = (query_humans.get_single(), query_bots.get_single()) else {
+        return;
+    };
+
+    let margin = (human.value as f32 - bot.value as f32) / DEFAULT_HEALTH as f32;
+
+    adaptive_difficulty.match_offset = (margin * ADAPTIVE_MARGIN_WEIGHT).clamp(-1., 1.);
+}
+
+// Each result nudges the bot towards an even win rate over the next matches
+fn follow_match_results(
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut adaptive_difficulty: ResMut<AdaptiveDifficulty>,
+    query_humans: Query<&Player, With<KeyAssignment>>,
+) {
+    let Ok(human) = query_humans.get_single() else {
+        return;
+    };
+
+    for ev in ev_game_over.read() {
+        adaptive_difficulty.matches += 1;
+
+        let step = match (ev.state, ev.player) {
+            (GameOvers::Winner, Some(winner)) if winner == human.value => {
+                adaptive_difficulty.human_wins += 1;
+                ADAPTIVE_MATCH_STEP
+            }
+            (GameOvers::Winner, Some(_)) => -ADAPTIVE_MATCH_STEP,
+            _ => 0.,
+        };
+
+        adaptive_difficulty.offset =
+            (adaptive_difficulty.offset + step).clamp(-ADAPTIVE_MAX_OFFSET, ADAPTIVE_MAX_OFFSET);
+        adaptive_difficulty.match_offset = 0.;
+    }
+}
+
+fn spawn_difficulty_text(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            right: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 15.,
+            ..default()
+        },
+        TextColor(Color::srgba(1., 1., 1., 0.5)),
+        TextLayout::new_with_justify(JustifyText::Right),
+        DifficultyText,
+        InGameEntity,
+    ));
+}
+
+fn difficulty_text_update(
+    adaptive_difficulty: Res<AdaptiveDifficulty>,
+    query_bots: Query<&AiController>,
+    mut query: Query<&mut Text, With<DifficultyText>>,
+) {
+    let Some(ai_controller) = query_bots
+        .iter()
+        .find(|ai_controller| ai_controller.adaptive)
+    else {
+        return;
+    };
+
+    let level = adaptive_difficulty.level(ai_controller.difficulty);
+
+    for mut text in &mut query {
+        **text = format!(
+            "Bot level: {:.2} (base {:?}, match {:+.2}, session {:+.2})\nHuman wins: {}/{}",
+            level,
+            ai_controller.difficulty,
+            adaptive_difficulty.match_offset,
+            adaptive_difficulty.offset,
+            adaptive_difficulty.human_wins,
+            adaptive_difficulty.matches
+        );
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        (enable_adaptive_difficulty, spawn_difficulty_text)
+            .after(spawn_players)
+            .run_if(adaptive_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::RoundingUp),
+        follow_health_margin.run_if(adaptive_playing),
+    );
+
+    app.add_systems(
+        Update,
+        (follow_match_results, difficulty_text_update)
+            .run_if(adaptive_playing)
+            .run_if(in_state(AppStates::InGame)),
+    );
+}
@@ -0,0 +1,232 @@
+// COMPUTER CONTROLLED PLAYERS
+use std::time::Duration;
+
+use super::*;
+
+fn learner_playing(query: Query<&AiController>) -> bool {
+    query.iter().any(|ai_controller| ai_controller.learning)
+}
+
+fn load_opponent_model(mut opponent_model: ResMut<OpponentModel>, settings: Res<MatchSettings>) {
+    *opponent_model = OpponentModel::load(settings.profile);
+}
+
+fn save_opponent_model(opponent_model: Res<OpponentModel>, settings: Res<MatchSettings>) {
+    if let Err(error) = opponent_model.save(settings.profile) {
+        warn!("Could not save opponent model: {}", error);
+    }
+}
+
+fn observe_human_choices(
+    mut opponent_model: ResMut<OpponentModel>,
+    query: Query<(&PlayerState, &Player), With<KeyAssignment>>,
+    query_views: Query<(&Health, &Bullets, &Dodges, &Player)>,
+    round_counter: Res<RoundCounter>,
+) {
+    for (player_state, player) in &query {
+        let Some(view) = duel_view(&query_views, player.value, round_counter.0) else {
+            continue;
+        };
+
+        let index = (player.value - 1) as usize;
+        let observed = Moves::from(player_state.0);
+        let context = ModelContext {
+            view,
+            previous: opponent_model.previous[index],
+        };
+
+        opponent_model.observe(&context, observed);
+        opponent_model.previous[index] = Some(observed);
+    }
+}
+
+fn remember_opponent_moves(
+    mut query: Query<(&mut AiController, &Target)>,
+    query_states: Query<(&PlayerState, &Player)>,
+) {
+    for (mut ai_controller, target) in &mut query {
+        for (player_state, player) in &query_states {
+            if player.value == target.value {
+                ai_controller.opponent_previous = Some(Moves::from(player_state.0));
+            }
+        }
+    }
+}
+
+fn taunt(
+    query: Query<(&AiController, &PlayerState, &Player)>,
+    personalities: Res<Assets<Personality>>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+) {
+    let mut rng = rand::rng();
+
+    for (ai_controller, player_state, player) in &query {
+        let Some(personality) = ai_controller
+            .personality
+            .as_ref()
+            .and_then(|handle| personalities.get(handle))
+        else {
+            continue;
+        };
+
+        if let Some(taunt) = personality.taunt(Moves::from(player_state.0), &mut rng) {
+            ev_tick_player.send(TickPlayerEvent {
+                player: player.value,
+                value: format!("\n\n{}", taunt),
+            });
+        }
+    }
+}
+
+fn plan_ai_decisions(
+    mut query: Query<(&mut AiController, &Player)>,
+    query_views: Query<(&Health, &Bullets, &Dodges, &Player)>,
+    round_counter: Res<RoundCounter>,
+    opponent_model: Res<OpponentModel>,
+    personalities: Res<Assets<Personality>>,
+    adaptive_difficulty: Res<AdaptiveDifficulty>,
+) {
+    let mut rng = rand::rng();
+
+    for (mut ai_controller, player) in &mut query {
+        let Some(view) = duel_view(&query_views, player.value, round_counter.0) else {
+            continue;
+        };
+
+        let level = if ai_controller.adaptive {
+            adaptive_difficulty.level(ai_controller.difficulty)
+        } else {
+            ai_controller.difficulty.level()
+        };
+        let difficulty = Difficulties::from_level(level, &mut rng);
+
+        // Predict the opponent from their side of the table
+        let prediction = ai_controller
+            .learning
+            .then(|| {
+                opponent_model.predict(&ModelContext {
+                    view: view.flipped(),
+                    previous: opponent_model.previous[(player.value % 2) as usize],
+                })
+            })
+            .flatten();
+
+        let personality = ai_controller
+            .personality
+            .as_ref()
+            .and_then(|handle| personalities.get(handle));
+
+        let (decision, reaction_range) = match (personality, prediction) {
+            (Some(personality), _) => (
+                personality.decide(&view, ai_controller.opponent_previous, &mut rng),
+                personality.reaction_range(),
+            ),
+            (None, Some(prediction)) => (
+                best_response(&view, prediction),
+                ai::blend_range(level, Difficulties::reaction_range),
+            ),
+            (None, None) => (
+                ai::decide(difficulty, &view, &mut rng),
+                ai::blend_range(level, Difficulties::reaction_range),
+            ),
+        };
+
+        ai_controller.decision = Some(decision);
+        ai_controller.reaction =
+            Timer::from_seconds(rng.random_range(reaction_range), TimerMode::Once);
+        ai_controller.draw_reaction = Duration::from_secs_f32(
+            rng.random_range(ai::blend_range(level, Difficulties::draw_range)),
+        );
+    }
+}
+
+fn play_ai_decisions(
+    mut query: Query<(
+        &mut AiController,
+        &mut PlayerState,
+        &mut QuickDraw,
+        &Bullets,
+        &Dodges,
+        &Player,
+    )>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    settings: Res<MatchSettings>,
+    draw_signal: Res<DrawSignal>,
+    time: Res<Time>,
+) {
+    for (mut ai_controller, mut player_state, mut quick_draw, bullets, dodges, player) in &mut query
+    {
+        let Some(decision) = ai_controller.decision else {
+            continue;
+        };
+
+        // In quick draw, shots are fired once the signal is given, never before
+        if settings.quick_draw && decision == PlayerStates::Attacking {
+            let Some(drawn_at) = draw_signal.drawn_at else {
+                continue;
+            };
+
+            let reaction = time.elapsed() - drawn_at;
+
+            if reaction < ai_controller.draw_reaction {
+                continue;
+            }
+
+            quick_draw.reaction = Some(reaction);
+        } else if !ai_controller.reaction.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        ai_controller.decision = None;
+        player_state.request(decision, bullets, dodges);
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        load_opponent_model
+            .after(spawn_players)
+            .run_if(learner_playing),
+    );
+
+    app.add_systems(OnEnter(PlayStates::Betting), plan_ai_decisions);
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        observe_human_choices
+            .before(betting::add_buffes)
+            .run_if(learner_playing),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        remember_opponent_moves.before(betting::add_buffes),
+    );
+
+    app.add_systems(OnEnter(PlayStates::Fighting), taunt);
+
+    app.add_systems(
+        OnEnter(PlayStates::GameOver),
+        save_opponent_model.run_if(learner_playing),
+    );
+
+    // Leaving mid-match keeps what was learned so far
+    app.add_systems(
+        OnExit(AppStates::InGame),
+        save_opponent_model
+            .before(clean_system::<InGameEntity>)
+            .run_if(learner_playing),
+    );
+
+    app.add_systems(
+        Update,
+        play_ai_decisions
+            .run_if(in_state(PlayStates::Betting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+}
@@ -0,0 +1,187 @@
+// AUDIENCE VOTING
+use super::*;
+
+// Online peers would each apply their own audience's pick, replays have theirs
+fn voting_enabled(
+    server: Option<Res<AudienceServer>>,
+    session: Option<Res<NetSession>>,
+    playback: Option<Res<ReplayPlayback>>,
+) -> bool {
+    server.is_some() && session.is_none() && playback.is_none()
+}
+
+fn modifier_active(active_modifier: Res<ActiveModifier>) -> bool {
+    active_modifier.0.is_some()
+}
+
+fn open_poll(server: Res<AudienceServer>, mut commands: Commands) {
+    let options = ROUND_MODIFIERS
+        .choose_multiple(&mut rand::rng(), AUDIENCE_OPTIONS)
+        .copied()
+        .collect();
+
+    server.open(options);
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(175.),
+            width: Val::Percent(100.),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(JustifyText::Center),
+        AudienceText,
+        InGameEntity,
+    ));
+}
+
+fn audience_text_update(
+    server: Res<AudienceServer>,
+    mut query: Query<&mut Text, With<AudienceText>>,
+) {
+    let poll = server.poll();
+    let tally = poll
+        .options
+        .iter()
+        .zip(&poll.votes)
+        .map(|(option, votes)| format!("{} {}", option.label(), votes))
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    for mut text in &mut query {
+        **text = format!("Audience vote on http://{}\n{}", server.address, tally);
+    }
+}
+
+fn close_poll(
+    server: Res<AudienceServer>,
+    mut active_modifier: ResMut<ActiveModifier>,
+    mut ev_alert: EventWriter<AlertEvent>,
+    mut commands: Commands,
+    query: Query<Entity, With<AudienceText>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    active_modifier.0 = server.close(&mut rand::rng());
+
+    if let Some(modifier) = active_modifier.0 {
+        ev_alert.send(AlertEvent {
+            value: format!("{}!", modifier.label()),
+        });
+    }
+}
+
+fn apply_round_start_modifier(active_modifier: Res<ActiveModifier>, mut query: Query<&mut Damage>) {
+    if active_modifier.0 == Some(RoundModifiers::DoubleDamage) {
+        for mut damage in &mut query {
+            damage.value *= 2;
+        }
+    }
+}
+
+// Drawn once the bets are in, players who buffed already keep their own buff
+fn grant_free_buff(
+    active_modifier: Res<ActiveModifier>,
+    mut duel_rng: ResMut<DuelRng>,
+    mut query: Query<(&mut Buff, &Player)>,
+) {
+    if active_modifier.0 != Some(RoundModifiers::FreeBuff) {
+        return;
+    }
+
+    for (mut buff, player) in &mut query {
+        if buff.value.is_none() {
+            buff.value = Some(duel_rng.player(player.value).random());
+        }
+    }
+}
+
+pub(super) fn forbid_dodges(
+    active_modifier: Res<ActiveModifier>,
+    mut query: Query<(&mut PlayerState, &Player)>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+) {
+    if active_modifier.0 != Some(RoundModifiers::NoDodges) {
+        return;
+    }
+
+    for (mut player_state, player) in &mut query {
+        if player_state.0 == PlayerStates::Dodging {
+            player_state.0 = PlayerStates::NotDodging;
+
+            ev_change_player_state.send(PlayerStateChangeEvent {
+                player: player.value,
+            });
+        }
+    }
+}
+
+fn end_modifier(mut active_modifier: ResMut<ActiveModifier>, mut query: Query<&mut Damage>) {
+    if active_modifier.0 == Some(RoundModifiers::DoubleDamage) {
+        for mut damage in &mut query {
+            damage.value = DEFAULT_DAMAGE;
+        }
+    }
+
+    active_modifier.0 = None;
+}
+
+fn clear_modifier(mut active_modifier: ResMut<ActiveModifier>) {
+    active_modifier.0 = None;
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(PlayStates::Preparing),
+        open_poll.run_if(voting_enabled),
+    );
+
+    app.add_systems(
+        Update,
+        audience_text_update
+            .run_if(voting_enabled)
+            .run_if(in_state(PlayStates::Preparing))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Preparing),
+        close_poll.run_if(voting_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Countdown),
+        apply_round_start_modifier.run_if(modifier_active),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Countdown),
+        despawn_alert_text.run_if(modifier_active),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        (
+            forbid_dodges.before(betting::add_buffes),
+            grant_free_buff
+                .after(betting::add_buffes)
+                .before(betting::spawn_buff_text)
+                .run_if(modifier_active),
+        ),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Fighting),
+        end_modifier.run_if(modifier_active),
+    );
+
+    app.add_systems(OnExit(AppStates::InGame), clear_modifier);
+}
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use bevy::input::{keyboard::KeyboardInput, ButtonState, InputSystem};
+
 use super::*;
 
 fn betting_countdown(
@@ -13,52 +17,115 @@ fn betting_countdown(
     }
 }
 
-fn reset_betting_timer(mut betting_timer: ResMut<BettingTimer>) {
+fn reset_betting_timer(mut betting_timer: ResMut<BettingTimer>, query: Query<&Handicap>) {
+    let extra_time = query
+        .iter()
+        .map(|handicap| handicap.betting_time)
+        .fold(0., f32::max);
+
+    betting_timer
+        .0
+        .set_duration(Duration::from_secs_f32(DEFAULT_BETTING_TIMER + extra_time));
     betting_timer.0.reset()
 }
 
-fn set_player_state(
-    mut query: Query<(&KeyAssignment, &mut PlayerState, &Dodges, &Bullets, &Player)>,
+// Keyboard events carry no timestamp of their own, so presses are stamped
+// with the betting clock of this frame. It keeps running after the betting
+// timer, which stops once finished and waits on the opponent online.
+fn stamp_key_presses(
+    mut ev_keyboard: EventReader<KeyboardInput>,
+    mut key_presses: ResMut<TimedKeyPresses>,
+    betting_timer: Res<BettingTimer>,
+    time: Res<Time>,
+) {
+    let at = time
+        .elapsed()
+        .saturating_sub(key_presses.betting_started_at);
+
+    // Nobody is told they were too late past the window
+    if at > betting_timer.0.duration() + Duration::from_secs_f32(TOO_LATE_WINDOW) {
+        ev_keyboard.clear();
+        return;
+    }
+
+    for ev in ev_keyboard.read() {
+        if ev.state == ButtonState::Pressed && !ev.repeat {
+            key_presses.presses.push(TimedKeyPress {
+                key: ev.key_code,
+                at,
+            });
+        }
+    }
+}
+
+fn reset_key_presses(mut key_presses: ResMut<TimedKeyPresses>, time: Res<Time>) {
+    key_presses.presses.clear();
+    key_presses.betting_started_at = time.elapsed();
+}
+
+pub(super) fn set_player_state(
+    mut query: Query<(
+        &KeyAssignment,
+        &mut PlayerState,
+        &Dodges,
+        &Bullets,
+        &Player,
+        &QuickDraw,
+        &Handicap,
+    )>,
     mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
-    keys: Res<ButtonInput<KeyCode>>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut key_presses: ResMut<TimedKeyPresses>,
 ) {
-    for key in keys.get_just_pressed() {
-        for (key_assignements, mut player_state, dodges, bullets, player) in &mut query {
-            let requested_state = key_assignements
-                .derive_player_state(key)
-                .unwrap_or(player_state.0);
+    // Presses of the same frame keep the order they were made in
+    for key_press in key_presses.presses.drain(..) {
+        for (key_assignements, mut player_state, dodges, bullets, player, quick_draw, handicap) in
+            &mut query
+        {
+            if quick_draw.false_start {
+                continue;
+            }
+
+            let derived_state = key_assignements.derive_player_state(&key_press.key);
+
+            // Players without extra betting time are locked once their own window closes
+            let deadline = Duration::from_secs_f32(DEFAULT_BETTING_TIMER + handicap.betting_time);
+
+            if key_press.at > deadline {
+                let late = key_press.at - deadline;
+
+                if derived_state.is_some() && late.as_secs_f32() <= TOO_LATE_WINDOW {
+                    ev_tick_player.send(TickPlayerEvent {
+                        player: player.value,
+                        value: format!("Too late!\n+{} ms", late.as_millis()),
+                    });
+                }
+
+                continue;
+            }
+
+            // Only the player whose key it was changes, so the other one doesn't
+            // look like they acted too
+            let Some(requested_state) = derived_state else {
+                continue;
+            };
 
             ev_change_player_state.send(PlayerStateChangeEvent {
                 player: player.value,
             });
 
-            match requested_state {
-                PlayerStates::Attacking => {
-                    if bullets.value > 0 {
-                        player_state.0 = requested_state;
-                    } else {
-                        player_state.0 = PlayerStates::NotAttacking;
-                    }
-                }
-                PlayerStates::Dodging => {
-                    if dodges.value > 0 {
-                        player_state.0 = requested_state;
-                    } else {
-                        player_state.0 = PlayerStates::NotDodging;
-                    }
-                }
-                other_player_state => player_state.0 = other_player_state,
-            }
+            player_state.request(requested_state, bullets, dodges);
         }
     }
 }
 
-fn add_buffes(
-    mut query: Query<(&mut Buff, &PlayerState), With<Player>>,
+pub(super) fn add_buffes(
+    mut query: Query<(&mut Buff, &PlayerState, &Player)>,
     mut commands: Commands,
     buff_audio: Res<assets::BuffAudio>,
+    mut duel_rng: ResMut<DuelRng>,
 ) {
-    for (mut buff, player_state) in &mut query {
+    for (mut buff, player_state, player) in &mut query {
         if player_state.0 == PlayerStates::Buffing {
             if let Some(audio) = buff_audio.audio.as_ref() {
                 commands.spawn((
@@ -69,7 +136,7 @@ fn add_buffes(
                 ));
             }
 
-            let random_buff: Buffes = rand::random();
+            let random_buff: Buffes = duel_rng.player(player.value).random();
             buff.value = Some(random_buff);
         }
     }
@@ -85,11 +152,7 @@ fn increase_damage_buff(
             if buff_value == Buffes::IncreaseDamageBuff {
                 damage.value += 1;
 
-                player_state.0 = if bullets.value > 0 {
-                    PlayerStates::Attacking
-                } else {
-                    PlayerStates::NotAttacking
-                };
+                player_state.0 = rules::forced_attack(bullets.value);
 
                 ev_change_player_state.send(PlayerStateChangeEvent {
                     player: player.value,
@@ -108,17 +171,14 @@ fn golden_bullet_buff(
     mut query: Query<(&Buff, &mut Damage, &mut PlayerState, &Bullets, &Player), With<Player>>,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
     mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    rule_set: Res<RuleSet>,
 ) {
     for (&buff, mut damage, mut player_state, bullets, player) in &mut query {
         if let Some(buff_value) = buff.value {
             if buff_value == Buffes::GoldenBulletBuff {
-                damage.value = 5;
+                damage.value = rule_set.golden_bullet_damage;
 
-                player_state.0 = if bullets.value > 0 {
-                    PlayerStates::Attacking
-                } else {
-                    PlayerStates::NotAttacking
-                };
+                player_state.0 = rules::forced_attack(bullets.value);
 
                 ev_change_player_state.send(PlayerStateChangeEvent {
                     player: player.value,
@@ -140,15 +200,15 @@ fn heal_buff(
     for (&buff, mut health, player) in &mut query {
         if let Some(buff_value) = buff.value {
             if buff_value == Buffes::HealBuff {
-                if health.value < DEFAULT_HEALTH {
-                    health.value += 1;
+                if health.value < health.max {
+                    health.value = rules::healed(health.value, health.max, 1);
 
                     ev_tick_player.send(TickPlayerEvent {
                         player: player.value,
                         value: "Healing".into(),
                     });
                 } else {
-                    health.value = DEFAULT_HEALTH;
+                    health.value = health.max;
 
                     ev_tick_player.send(TickPlayerEvent {
                         player: player.value,
@@ -167,15 +227,15 @@ fn super_heal_buff(
     for (&buff, mut health, player) in &mut query {
         if let Some(buff_value) = buff.value {
             if buff_value == Buffes::SuperHealBuff {
-                if health.value < DEFAULT_HEALTH - 1 {
-                    health.value += 2;
+                if health.value < health.max - 1 {
+                    health.value = rules::healed(health.value, health.max, 2);
 
                     ev_tick_player.send(TickPlayerEvent {
                         player: player.value,
                         value: "Super healing".into(),
                     });
                 } else {
-                    health.value = DEFAULT_HEALTH;
+                    health.value = health.max;
 
                     ev_tick_player.send(TickPlayerEvent {
                         player: player.value,
@@ -190,11 +250,14 @@ fn super_heal_buff(
 fn luck_buff(
     mut query: Query<(&Buff, &mut Luck, &Player), With<Player>>,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    rule_set: Res<RuleSet>,
 ) {
     for (&buff, mut luck, player) in &mut query {
         if let Some(buff_value) = buff.value {
             if buff_value == Buffes::LuckBuff {
-                luck.value += Dice { value: 50 };
+                luck.value += Dice {
+                    value: rule_set.buff_dice_bonus,
+                };
 
                 ev_tick_player.send(TickPlayerEvent {
                     player: player.value,
@@ -218,18 +281,17 @@ fn marksmanship_buff(
     >,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
     mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    rule_set: Res<RuleSet>,
 ) {
     for (&buff, mut marksmanship, player, bullets, mut player_state) in &mut query {
         if let Some(buff_value) = buff.value {
             if buff_value == Buffes::MarksmanshipBuff {
-                marksmanship.value += Dice { value: 50 };
-
-                player_state.0 = if bullets.value > 0 {
-                    PlayerStates::Attacking
-                } else {
-                    PlayerStates::NotAttacking
+                marksmanship.value += Dice {
+                    value: rule_set.buff_dice_bonus,
                 };
 
+                player_state.0 = rules::forced_attack(bullets.value);
+
                 ev_change_player_state.send(PlayerStateChangeEvent {
                     player: player.value,
                 });
@@ -243,7 +305,7 @@ fn marksmanship_buff(
     }
 }
 
-fn spawn_buff_text(
+pub(super) fn spawn_buff_text(
     mut commands: Commands,
     window_query: Query<&Window>,
     query: Query<(&Player, &PlayerState, &Buff), With<Player>>,
@@ -299,12 +361,32 @@ fn update_betting_timer_ui(
 pub fn plugin(app: &mut App) {
     app.add_systems(
         OnEnter(PlayStates::Betting),
-        (reset_betting_timer, spawn_timer_ui).chain(),
+        (reset_betting_timer, reset_key_presses, spawn_timer_ui).chain(),
+    );
+
+    app.add_systems(
+        PreUpdate,
+        stamp_key_presses
+            .after(InputSystem)
+            .run_if(in_state(PlayStates::Betting).or(in_state(PlayStates::Fighting)))
+            .run_if(in_state(AppStates::InGame)),
     );
 
+    // Presses just after betting closed only get their feedback
     app.add_systems(
         Update,
-        (betting_countdown, set_player_state, update_betting_timer_ui)
+        set_player_state
+            .run_if(in_state(PlayStates::Fighting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            betting_countdown.run_if(not(resource_exists::<NetSession>)),
+            set_player_state,
+            update_betting_timer_ui,
+        )
             .run_if(in_state(PlayStates::Betting))
             .run_if(in_state(AppStates::InGame)),
     );
@@ -313,6 +395,8 @@ pub fn plugin(app: &mut App) {
         OnExit(PlayStates::Betting),
         (
             despawn_timer_ui,
+            // Presses that came with the closing frame only get their feedback
+            set_player_state.before(add_buffes),
             (
                 add_buffes,
                 spawn_buff_text,
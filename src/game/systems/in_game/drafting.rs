@@ -0,0 +1,224 @@
+use super::*;
+
+fn reset_draft_turn(mut draft_turn: ResMut<DraftTurn>) {
+    draft_turn.0 = 1;
+}
+
+fn spawn_draft_ui(
+    mut commands: Commands,
+    query: Query<(&Player, &Transform), With<Player>>,
+    window: Single<&Window>,
+) {
+    let dimensions = [400., 200.];
+
+    for (player, transform) in &query {
+        commands.spawn((
+            Node {
+                width: Val::Px(dimensions[0]),
+                height: Val::Px(dimensions[1]),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(transform.translation.y + window.height() / 2. + 250.),
+                left: Val::Px(transform.translation.x + window.width() / 2. - (dimensions[0] / 2.)),
+                ..default()
+            },
+            Text::default(),
+            TextFont {
+                font_size: DEFAULT_FONT_SIZE * 0.2,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(JustifyText::Center),
+            DraftText {
+                value: player.value,
+            },
+            InGameEntity,
+        ));
+    }
+}
+
+fn despawn_draft_ui(mut commands: Commands, query: Query<Entity, With<DraftText>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn draft_text_update(
+    draft_turn: Res<DraftTurn>,
+    query: Query<(&DraftPicks, &Player)>,
+    mut query_ui: Query<(&DraftText, &mut Text)>,
+) {
+    for (draft_text, mut text) in &mut query_ui {
+        for (draft_picks, player) in &query {
+            if draft_text.value == player.value {
+                let header = if draft_picks.spent() >= N_DRAFT_PICKS {
+                    "Done".to_string()
+                } else if draft_turn.0 == player.value {
+                    format!("Your pick! ({} left)", N_DRAFT_PICKS - draft_picks.spent())
+                } else {
+                    "Waiting...".to_string()
+                };
+
+                let lines: Vec<String> = DRAFT_STATS
+                    .iter()
+                    .enumerate()
+                    .map(|(index, stat)| {
+                        let prefix = if index == draft_picks.cursor {
+                            "> "
+                        } else {
+                            ""
+                        };
+                        format!("{}{:?} +{}", prefix, stat, draft_picks.allocation[index])
+                    })
+                    .collect();
+
+                **text = format!("{}\n{}", header, lines.join("\n"));
+            }
+        }
+    }
+}
+
+type DraftedStats<'a> = (
+    &'a mut Health,
+    &'a mut Bullets,
+    &'a mut Dodges,
+    &'a mut Luck,
+    &'a mut Marksmanship,
+);
+
+fn apply_pick(stat: DraftStats, query_stats: &mut Query<DraftedStats>, entity: Entity) {
+    if let Ok((mut health, mut bullets, mut dodges, mut luck, mut marksmanship)) =
+        query_stats.get_mut(entity)
+    {
+        match stat {
+            DraftStats::Health => {
+                health.value += 1;
+                health.max += 1;
+            }
+            DraftStats::Bullets => bullets.value += 1,
+            DraftStats::Dodges => dodges.value += 1,
+            DraftStats::Luck => {
+                luck.base += Dice {
+                    value: DRAFT_DICE_BONUS,
+                };
+                luck.value = luck.base;
+            }
+            DraftStats::Marksmanship => {
+                marksmanship.base += Dice {
+                    value: DRAFT_DICE_BONUS,
+                };
+                marksmanship.value = marksmanship.base;
+            }
+        }
+    }
+}
+
+fn pick_stats(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut draft_turn: ResMut<DraftTurn>,
+    mut query: Query<(Entity, &KeyAssignment, &mut DraftPicks, &Player)>,
+    mut query_stats: Query<DraftedStats>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+) {
+    for key in keys.get_just_pressed() {
+        for (entity, key_assignements, mut draft_picks, player) in &mut query {
+            if *key == key_assignements.0[0] {
+                draft_picks.cursor =
+                    (draft_picks.cursor + DRAFT_STATS.len() - 1) % DRAFT_STATS.len();
+            } else if *key == key_assignements.0[1] {
+                draft_picks.cursor = (draft_picks.cursor + 1) % DRAFT_STATS.len();
+            } else if *key == key_assignements.0[2]
+                && draft_turn.0 == player.value
+                && draft_picks.spent() < N_DRAFT_PICKS
+            {
+                let cursor = draft_picks.cursor;
+                let stat = DRAFT_STATS[cursor];
+                draft_picks.allocation[cursor] += 1;
+                apply_pick(stat, &mut query_stats, entity);
+
+                ev_tick_player.send(TickPlayerEvent {
+                    player: player.value,
+                    value: format!("+{:?}", stat),
+                });
+
+                draft_turn.0 = player.value % 2 + 1;
+            }
+        }
+    }
+}
+
+// External bots are only asked for their moves, they draft like the AI
+type BotDrafters = Or<(With<AiController>, With<ExternalBot>)>;
+
+fn ai_pick_stats(
+    mut draft_turn: ResMut<DraftTurn>,
+    mut query: Query<(Entity, &mut DraftPicks, &Player), BotDrafters>,
+    query_views: Query<(&Health, &Bullets, &Dodges, &Player)>,
+    mut query_stats: Query<DraftedStats>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    round_counter: Res<RoundCounter>,
+) {
+    for (entity, mut draft_picks, player) in &mut query {
+        if draft_turn.0 != player.value || draft_picks.spent() >= N_DRAFT_PICKS {
+            continue;
+        }
+
+        let Some(view) = duel_view(&query_views, player.value, round_counter.0) else {
+            continue;
+        };
+
+        let stat = ai::draft(&view, &mut rand::rng());
+        let index = DRAFT_STATS
+            .iter()
+            .position(|other| *other == stat)
+            .unwrap_or(0);
+
+        draft_picks.cursor = index;
+        draft_picks.allocation[index] += 1;
+        apply_pick(stat, &mut query_stats, entity);
+
+        ev_tick_player.send(TickPlayerEvent {
+            player: player.value,
+            value: format!("+{:?}", stat),
+        });
+
+        draft_turn.0 = player.value % 2 + 1;
+    }
+}
+
+// Skip the turn of a player who already spent their budget
+fn skip_spent_turns(mut draft_turn: ResMut<DraftTurn>, query: Query<(&DraftPicks, &Player)>) {
+    for (draft_picks, player) in &query {
+        if draft_turn.0 == player.value && draft_picks.spent() >= N_DRAFT_PICKS {
+            draft_turn.0 = player.value % 2 + 1;
+        }
+    }
+}
+
+fn check_draft_ended(query: Query<&DraftPicks>) -> bool {
+    query
+        .iter()
+        .all(|draft_picks| draft_picks.spent() >= N_DRAFT_PICKS)
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(PlayStates::Drafting),
+        (reset_draft_turn, spawn_draft_ui),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            pick_stats,
+            ai_pick_stats,
+            skip_spent_turns,
+            draft_text_update,
+            next_play_state.run_if(check_draft_ended),
+        )
+            .chain()
+            .run_if(in_state(PlayStates::Drafting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(OnExit(PlayStates::Drafting), despawn_draft_ui);
+}
@@ -0,0 +1,174 @@
+// EXTERNAL BOTS
+use super::*;
+
+fn start_external_bots(
+    mut query: Query<(&mut ExternalBot, &Player)>,
+    configs: Res<Assets<ExternalBotConfig>>,
+) {
+    for (mut external_bot, player) in &mut query {
+        let Some(config) = configs.get(&external_bot.config) else {
+            continue;
+        };
+
+        match BotProcess::spawn(config) {
+            Ok(process) => external_bot.process = Some(process),
+            Err(error) => warn!(
+                "Could not start bot {} for player {}: {}",
+                config.name, player.value, error
+            ),
+        }
+    }
+}
+
+fn send_bot_requests(
+    mut query: Query<(&mut ExternalBot, &Handicap, &Player)>,
+    query_views: Query<(&Health, &Bullets, &Dodges, &Player)>,
+    round_counter: Res<RoundCounter>,
+) {
+    for (mut external_bot, handicap, player) in &mut query {
+        let Some(view) = duel_view(&query_views, player.value, round_counter.0) else {
+            continue;
+        };
+
+        external_bot.answered = false;
+        external_bot.decision = None;
+        external_bot.health_at_betting = [view.me.health, view.opponent.health];
+
+        let request = BotRequest {
+            player: player.value,
+            round: view.round,
+            max_round: N_MAX_ROUND,
+            time_limit: DEFAULT_BETTING_TIMER + handicap.betting_time,
+            me: view.me,
+            opponent: view.opponent,
+            last_round: external_bot.last_round,
+        };
+
+        let Some(process) = external_bot.process.as_mut() else {
+            continue;
+        };
+
+        process.drain();
+
+        if let Err(error) = process.send(&request) {
+            warn!("Could not reach bot of player {}: {}", player.value, error);
+        }
+    }
+}
+
+// Only the first line of each round counts, anything unreadable leaves the bot idle
+fn read_bot_responses(mut query: Query<(&mut ExternalBot, &Player)>) {
+    for (mut external_bot, player) in &mut query {
+        if external_bot.answered {
+            continue;
+        }
+
+        let Some(line) = external_bot
+            .process
+            .as_ref()
+            .and_then(|process| process.try_read())
+        else {
+            continue;
+        };
+
+        external_bot.answered = true;
+
+        match serde_json::from_str::<BotResponse>(&line) {
+            Ok(response) => external_bot.decision = Some(PlayerStates::from(response.action)),
+            Err(error) => warn!(
+                "Ignoring malformed answer from bot of player {}: {}",
+                player.value, error
+            ),
+        }
+    }
+}
+
+fn play_bot_decisions(
+    mut query: Query<(
+        &mut ExternalBot,
+        &mut PlayerState,
+        &mut QuickDraw,
+        &Bullets,
+        &Dodges,
+        &Player,
+    )>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    settings: Res<MatchSettings>,
+    draw_signal: Res<DrawSignal>,
+    time: Res<Time>,
+) {
+    for (mut external_bot, mut player_state, mut quick_draw, bullets, dodges, player) in &mut query
+    {
+        let Some(decision) = external_bot.decision else {
+            continue;
+        };
+
+        // In quick draw, shots wait for the signal like everybody else's
+        if settings.quick_draw && decision == PlayerStates::Attacking {
+            let Some(drawn_at) = draw_signal.drawn_at else {
+                continue;
+            };
+
+            quick_draw.reaction = Some(time.elapsed() - drawn_at);
+        }
+
+        external_bot.decision = None;
+        player_state.request(decision, bullets, dodges);
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+}
+
+fn record_round_outcomes(
+    mut query: Query<(&mut ExternalBot, &Target)>,
+    query_players: Query<(&PlayerState, &Health, &Player)>,
+) {
+    for (mut external_bot, target) in &mut query {
+        let [mut me, mut opponent] = [None; 2];
+
+        for (player_state, health, player) in &query_players {
+            let side = (Moves::from(player_state.0), health.value);
+
+            if player.value == target.value {
+                opponent = Some(side);
+            } else {
+                me = Some(side);
+            }
+        }
+
+        let (Some((my_move, my_health)), Some((opponent_move, opponent_health))) = (me, opponent)
+        else {
+            continue;
+        };
+
+        let [my_health_at_betting, opponent_health_at_betting] = external_bot.health_at_betting;
+
+        external_bot.last_round = Some(RoundOutcome {
+            me: my_move,
+            opponent: opponent_move,
+            damage_taken: my_health_at_betting.saturating_sub(my_health),
+            damage_dealt: opponent_health_at_betting.saturating_sub(opponent_health),
+        });
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        start_external_bots.after(spawn_players),
+    );
+
+    app.add_systems(OnEnter(PlayStates::Betting), send_bot_requests);
+
+    app.add_systems(
+        Update,
+        (read_bot_responses, play_bot_decisions)
+            .chain()
+            .run_if(in_state(PlayStates::Betting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(OnExit(PlayStates::Fighting), record_round_outcomes);
+}
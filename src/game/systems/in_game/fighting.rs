@@ -1,15 +1,30 @@
+use std::time::Duration;
+
 use super::*;
 
 fn fight(
-    query: Query<(&PlayerState, &Player, &Target, &Damage, &Marksmanship), With<Player>>,
+    query: Query<(
+        &PlayerState,
+        &Player,
+        &Target,
+        &Damage,
+        &Marksmanship,
+        &QuickDraw,
+    )>,
     mut ev_attack: EventWriter<AttackEvent>,
     mut ev_depleted: EventWriter<DepletedEvent>,
 ) {
-    for (player_state, player, target, damage, marksmanship) in &query {
+    let mut fighters: Vec<_> = query.iter().collect();
+
+    // Quickest draw fires first (no-op outside of quick draw mode)
+    fighters.sort_by_key(|(.., quick_draw)| quick_draw.reaction.unwrap_or(Duration::MAX));
+
+    for (player_state, player, target, damage, marksmanship, _) in fighters {
         match player_state.0 {
             PlayerStates::Attacking => {
                 ev_attack.send(AttackEvent {
                     player: target.value,
+                    attacker: player.value,
                     damage: damage.value,
                     marksmanship: marksmanship.value,
                 });
@@ -62,6 +77,7 @@ fn listen_attack_event(
 
                         ev_damage.send(DamageEvent {
                             player: player.value,
+                            attacker: ev.attacker,
                             value: ev.damage,
                             marksmanship: ev.marksmanship,
                         });
@@ -72,19 +88,46 @@ fn listen_attack_event(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn listen_damage_event(
     mut ev_damage: EventReader<DamageEvent>,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut ev_shot_rolled: EventWriter<ShotRolledEvent>,
     mut ev_missed: EventWriter<MissedEvent>,
     mut query: Query<(&mut Health, &Player, &Luck), With<Player>>,
     mut commands: Commands,
     damage_audio: Res<assets::DamageAudio>,
+    settings: Res<MatchSettings>,
+    mut duel_rng: ResMut<DuelRng>,
 ) {
     for ev in ev_damage.read() {
+        // In quick draw, a shooter taken down by a faster draw never pulls the trigger
+        if settings.quick_draw
+            && query
+                .iter()
+                .any(|(health, player, _)| player.value == ev.attacker && health.value == 0)
+        {
+            ev_tick_player.send(TickPlayerEvent {
+                player: ev.attacker,
+                value: "\nToo slow!".into(),
+            });
+            continue;
+        }
+
         for (mut health, player, luck) in &mut query {
             if player.value == ev.player {
-                if ev.marksmanship.roll() > luck.value.roll() {
-                    health.value = health.value.checked_sub(ev.value).unwrap_or(0);
+                let roll =
+                    rules::roll_hit(ev.marksmanship, luck.value, duel_rng.player(player.value));
+
+                ev_shot_rolled.send(ShotRolledEvent {
+                    player: player.value,
+                    attacker: ev.attacker,
+                    damage: ev.value,
+                    roll,
+                });
+
+                if roll.hits() {
+                    health.value = health.value.saturating_sub(ev.value);
 
                     if let Some(audio) = damage_audio.audio.as_ref() {
                         commands.spawn((
@@ -234,9 +277,7 @@ fn damage_reset(mut query: Query<(&mut Damage, &PlayerState), With<Player>>) {
 fn luck_reset(mut query: Query<(&mut Luck, &PlayerState), With<Player>>) {
     for (mut luck, player_state) in &mut query {
         if player_state.0 != PlayerStates::Buffing {
-            luck.value = Dice {
-                value: DEFAULT_LUCK,
-            };
+            luck.value = luck.base;
         }
     }
 }
@@ -244,9 +285,7 @@ fn luck_reset(mut query: Query<(&mut Luck, &PlayerState), With<Player>>) {
 fn marksmanship_reset(mut query: Query<(&mut Marksmanship, &PlayerState), With<Player>>) {
     for (mut marksmanship, player_state) in &mut query {
         if player_state.0 != PlayerStates::Buffing {
-            marksmanship.value = Dice {
-                value: DEFAULT_MARKSMANSHIP,
-            };
+            marksmanship.value = marksmanship.base;
         }
     }
 }
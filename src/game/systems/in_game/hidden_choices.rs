@@ -0,0 +1,97 @@
+// HIDDEN CHOICES MODE
+use super::*;
+
+fn hidden_choices_enabled(settings: Res<MatchSettings>) -> bool {
+    settings.hidden_choices
+}
+
+pub(super) fn choices_revealed(
+    settings: Res<MatchSettings>,
+    play_state: Res<State<PlayStates>>,
+) -> bool {
+    !settings.hidden_choices || *play_state.get() != PlayStates::Betting
+}
+
+fn listen_spawn_locked_indicator(
+    mut commands: Commands,
+    mut ev_player_state: EventReader<PlayerStateChangeEvent>,
+    query: Query<(&Player, &Transform), With<Player>>,
+    query_locked: Query<&LockedText>,
+    window: Single<&Window>,
+) {
+    let mut locked: Vec<u8> = query_locked.iter().map(|locked| locked.value).collect();
+    let dimensions = [250., 50.];
+
+    for ev in ev_player_state.read() {
+        if locked.contains(&ev.player) {
+            continue;
+        }
+
+        for (player, transform) in &query {
+            if player.value == ev.player {
+                commands.spawn((
+                    Node {
+                        width: Val::Px(dimensions[0]),
+                        height: Val::Px(dimensions[1]),
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(transform.translation.y + window.height() / 2. + 250.),
+                        left: Val::Px(
+                            transform.translation.x + window.width() / 2. - (dimensions[0] / 2.),
+                        ),
+                        ..default()
+                    },
+                    Text::new("Locked"),
+                    TextFont {
+                        font_size: DEFAULT_FONT_SIZE * 0.25,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    LockedText {
+                        value: player.value,
+                    },
+                    InGameEntity,
+                ));
+
+                locked.push(player.value);
+            }
+        }
+    }
+}
+
+fn despawn_locked_indicators(mut commands: Commands, query: Query<Entity, With<LockedText>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn reveal_choices(
+    query: Query<&Player>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+) {
+    for player in &query {
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        listen_spawn_locked_indicator
+            .run_if(hidden_choices_enabled)
+            .run_if(in_state(PlayStates::Betting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        despawn_locked_indicators.run_if(hidden_choices_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Fighting),
+        reveal_choices.run_if(hidden_choices_enabled),
+    );
+}
@@ -0,0 +1,46 @@
+// MATCH HISTORY
+use super::*;
+
+fn reset_match_history(mut match_history: ResMut<MatchHistory>) {
+    match_history.rounds.clear();
+}
+
+pub(super) fn record_round_start(
+    mut match_history: ResMut<MatchHistory>,
+    round: Res<RoundCounter>,
+    query: Query<(&Health, &Bullets, &Dodges, &Player)>,
+) {
+    let mut entry = RoundHistory {
+        round: round.0,
+        health: [0; 2],
+        bullets: [0; 2],
+        dodges: [0; 2],
+        win_chances: None,
+    };
+
+    for (health, bullets, dodges, player) in &query {
+        let index = (player.value - 1) as usize;
+
+        entry.health[index] = health.value;
+        entry.bullets[index] = bullets.value;
+        entry.dodges[index] = dodges.value;
+    }
+
+    match_history.rounds.push(entry);
+}
+
+// The first round skips the preparing phase
+fn first_round(match_history: Res<MatchHistory>) -> bool {
+    match_history.rounds.is_empty()
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::InGame), reset_match_history);
+
+    app.add_systems(OnEnter(PlayStates::Preparing), record_round_start);
+
+    app.add_systems(
+        OnEnter(PlayStates::Countdown),
+        record_round_start.run_if(first_round),
+    );
+}
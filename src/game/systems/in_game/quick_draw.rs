@@ -0,0 +1,118 @@
+// QUICK DRAW MODE
+use super::*;
+
+fn quick_draw_enabled(settings: Res<MatchSettings>) -> bool {
+    settings.quick_draw
+}
+
+fn reset_draw_signal(
+    mut draw_signal: ResMut<DrawSignal>,
+    mut query: Query<&mut QuickDraw, With<Player>>,
+) {
+    let mut rng = rand::rng();
+    let delay = rng.random_range(QUICK_DRAW_MIN_DELAY..QUICK_DRAW_MAX_DELAY);
+
+    draw_signal.timer = Timer::from_seconds(delay, TimerMode::Once);
+    draw_signal.drawn_at = None;
+
+    for mut quick_draw in &mut query {
+        *quick_draw = QuickDraw::default();
+    }
+}
+
+fn tick_draw_signal(
+    mut draw_signal: ResMut<DrawSignal>,
+    mut ev_alert: EventWriter<AlertEvent>,
+    time: Res<Time>,
+) {
+    draw_signal.timer.tick(time.delta());
+
+    if draw_signal.timer.just_finished() {
+        draw_signal.drawn_at = Some(time.elapsed());
+
+        ev_alert.send(AlertEvent {
+            value: "DRAW!".into(),
+        });
+    }
+}
+
+fn register_draws(
+    mut query: Query<(&KeyAssignment, &mut PlayerState, &mut QuickDraw, &Player)>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    draw_signal: Res<DrawSignal>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    for key in keys.get_just_pressed() {
+        for (key_assignements, mut player_state, mut quick_draw, player) in &mut query {
+            if quick_draw.false_start
+                || key_assignements.derive_player_state(key) != Some(PlayerStates::Attacking)
+            {
+                continue;
+            }
+
+            match draw_signal.drawn_at {
+                None => {
+                    quick_draw.false_start = true;
+                    player_state.0 = PlayerStates::Idle;
+
+                    ev_change_player_state.send(PlayerStateChangeEvent {
+                        player: player.value,
+                    });
+
+                    ev_tick_player.send(TickPlayerEvent {
+                        player: player.value,
+                        value: "False start!".into(),
+                    });
+                }
+                Some(drawn_at) => {
+                    if quick_draw.reaction.is_none() {
+                        quick_draw.reaction = Some(time.elapsed() - drawn_at);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn report_reaction_times(
+    query: Query<(&QuickDraw, &Player)>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+) {
+    for (quick_draw, player) in &query {
+        if let Some(reaction) = quick_draw.reaction {
+            ev_tick_player.send(TickPlayerEvent {
+                player: player.value,
+                value: format!("\n\n\n{} ms", reaction.as_millis()),
+            });
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(PlayStates::Betting),
+        reset_draw_signal.run_if(quick_draw_enabled),
+    );
+
+    app.add_systems(
+        Update,
+        (tick_draw_signal, register_draws)
+            .chain()
+            .before(betting::set_player_state)
+            .run_if(quick_draw_enabled)
+            .run_if(in_state(PlayStates::Betting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        despawn_alert_text.run_if(quick_draw_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Fighting),
+        report_reaction_times.run_if(quick_draw_enabled),
+    );
+}
@@ -0,0 +1,183 @@
+// REPLAY RECORDING
+use super::*;
+
+// Demos and replays are not worth keeping. Quick draws are decided by
+// reaction times the replay can't play back, so they are not recorded either
+fn recording(
+    attract_mode: Res<AttractMode>,
+    playback: Option<Res<ReplayPlayback>>,
+    settings: Res<MatchSettings>,
+) -> bool {
+    !attract_mode.active && playback.is_none() && !settings.quick_draw
+}
+
+fn recording_started(recorder: Res<ReplayRecorder>) -> bool {
+    recorder.replay.is_some()
+}
+
+fn reset_recorder(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.replay = None;
+}
+
+type RecordedStats<'a> = (
+    &'a Health,
+    &'a Bullets,
+    &'a Dodges,
+    &'a Damage,
+    &'a Luck,
+    &'a Marksmanship,
+    &'a Buff,
+    &'a Handicap,
+    &'a Player,
+);
+
+// Once drafts, handicaps and scenarios have shaped the fighters
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    rule_set: Res<RuleSet>,
+    duel_rng: Res<DuelRng>,
+    round_counter: Res<RoundCounter>,
+    query: Query<RecordedStats>,
+) {
+    let mut fighters = [ReplayFighter {
+        health: 0,
+        max_health: 0,
+        bullets: 0,
+        dodges: 0,
+        damage: 0,
+        luck: 0,
+        base_luck: 0,
+        marksmanship: 0,
+        base_marksmanship: 0,
+        buff: None,
+    }; 2];
+    let mut handicaps = [Handicap::default(); 2];
+
+    for (health, bullets, dodges, damage, luck, marksmanship, buff, handicap, player) in &query {
+        let index = (player.value - 1) as usize;
+
+        fighters[index] = ReplayFighter {
+            health: health.value,
+            max_health: health.max,
+            bullets: bullets.value,
+            dodges: dodges.value,
+            damage: damage.value,
+            luck: luck.value.value,
+            base_luck: luck.base.value,
+            marksmanship: marksmanship.value.value,
+            base_marksmanship: marksmanship.base.value,
+            buff: buff.value,
+        };
+        handicaps[index] = *handicap;
+    }
+
+    recorder.replay = Some(Replay {
+        rules: *rule_set,
+        seed: duel_rng.seed,
+        handicaps,
+        first_round: round_counter.0,
+        fighters,
+        rounds: vec![],
+    });
+}
+
+fn reset_choice_times(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.choice_states = [None; 2];
+    recorder.choice_times = [None; 2];
+}
+
+// Only actual changes count, state change events also go to idle players
+fn record_choice_times(
+    mut recorder: ResMut<ReplayRecorder>,
+    betting_timer: Res<BettingTimer>,
+    query: Query<(&PlayerState, &Player), Changed<PlayerState>>,
+) {
+    for (player_state, player) in &query {
+        let index = (player.value - 1) as usize;
+
+        if recorder.choice_states[index] != Some(player_state.0) {
+            recorder.choice_states[index] = Some(player_state.0);
+            recorder.choice_times[index] = Some(betting_timer.0.elapsed_secs());
+        }
+    }
+}
+
+fn record_round(
+    mut recorder: ResMut<ReplayRecorder>,
+    round_counter: Res<RoundCounter>,
+    active_modifier: Res<ActiveModifier>,
+    query: Query<(&PlayerState, &Player)>,
+) {
+    let choice_times = recorder.choice_times;
+    let Some(replay) = recorder.replay.as_mut() else {
+        return;
+    };
+
+    let mut choices = [ReplayChoice {
+        choice: Moves::Idle,
+        at: None,
+    }; 2];
+
+    for (player_state, player) in &query {
+        let index = (player.value - 1) as usize;
+
+        choices[index] = ReplayChoice {
+            choice: Moves::from(player_state.0),
+            at: choice_times[index],
+        };
+    }
+
+    replay.rounds.push(ReplayRound {
+        round: round_counter.0,
+        modifier: active_modifier.0,
+        choices,
+    });
+}
+
+fn save_replay(recorder: Res<ReplayRecorder>) {
+    let Some(replay) = recorder.replay.as_ref() else {
+        return;
+    };
+
+    match replay.save() {
+        Ok(path) => info!("Replay saved to {}", path.display()),
+        Err(error) => warn!("Could not save replay: {}", error),
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::InGame), reset_recorder);
+
+    app.add_systems(
+        OnEnter(PlayStates::Countdown),
+        start_recording
+            .run_if(recording)
+            .run_if(not(recording_started)),
+    );
+
+    app.add_systems(OnEnter(PlayStates::Betting), reset_choice_times);
+
+    app.add_systems(
+        Update,
+        record_choice_times
+            .run_if(recording_started)
+            .run_if(in_state(PlayStates::Betting))
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    // Choices are final once scripts and modifiers had their say, before
+    // buffs force their own moves
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        record_round
+            .after(scenario::play_scripted_actions)
+            .after(audience_voting::forbid_dodges)
+            .before(betting::add_buffes)
+            .run_if(recording_started),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::GameOver),
+        save_replay.run_if(recording_started),
+    );
+}
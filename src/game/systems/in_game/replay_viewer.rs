@@ -0,0 +1,356 @@
+// REPLAY VIEWER
+use super::*;
+
+fn replaying(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_some()
+}
+
+fn spawn_replay_hint(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2. + 30.),
+            right: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new("Press R to watch the last match"),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        GlobalZIndex(1),
+        MenuEntity,
+    ));
+}
+
+fn open_last_replay(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<MatchSettings>,
+    mut rule_set: ResMut<RuleSet>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut commands: Commands,
+) {
+    if !keys.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+
+    let replay = match Replay::latest() {
+        Ok(replay) => replay,
+        Err(error) => {
+            warn!("Could not load the last replay: {}", error);
+            return;
+        }
+    };
+
+    // Fighters are set up from the replay, drafts and scenarios are skipped
+    let replay_settings = MatchSettings {
+        handicaps: replay.handicaps,
+        controllers: [Controllers::Replay; 2],
+        fixed_difficulty: true,
+        seed: Some(replay.seed),
+        ..default()
+    };
+    let saved_rules = std::mem::replace(&mut *rule_set, replay.rules);
+    let saved_settings = std::mem::replace(&mut *settings, replay_settings);
+
+    commands.insert_resource(ReplayPlayback::new(replay, saved_settings, saved_rules));
+    next_app_state.set(AppStates::InGame);
+}
+
+type ReplayedStats<'a> = (
+    &'a mut Health,
+    &'a mut Bullets,
+    &'a mut Dodges,
+    &'a mut Damage,
+    &'a mut Luck,
+    &'a mut Marksmanship,
+    &'a mut Buff,
+    &'a Player,
+);
+
+fn setup_replayed_fighters(
+    playback: Res<ReplayPlayback>,
+    mut round_counter: ResMut<RoundCounter>,
+    mut query: Query<ReplayedStats>,
+) {
+    round_counter.0 = playback.replay.first_round;
+
+    for (
+        mut health,
+        mut bullets,
+        mut dodges,
+        mut damage,
+        mut luck,
+        mut marksmanship,
+        mut buff,
+        player,
+    ) in &mut query
+    {
+        let fighter = playback.replay.fighters[(player.value - 1) as usize];
+
+        health.value = fighter.health;
+        health.max = fighter.max_health;
+        bullets.value = fighter.bullets;
+        dodges.value = fighter.dodges;
+        damage.value = fighter.damage;
+        luck.value.value = fighter.luck;
+        luck.base.value = fighter.base_luck;
+        marksmanship.value.value = fighter.marksmanship;
+        marksmanship.base.value = fighter.base_marksmanship;
+        buff.value = fighter.buff;
+    }
+}
+
+fn spawn_replay_text(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::srgba(1., 1., 1., 0.75)),
+        GlobalZIndex(3),
+        ReplayText,
+        InGameEntity,
+    ));
+}
+
+fn replay_text_update(
+    playback: Res<ReplayPlayback>,
+    time: Res<Time<Virtual>>,
+    mut query: Query<&mut Text, With<ReplayText>>,
+) {
+    let status = if time.is_paused() {
+        "Paused".to_string()
+    } else {
+        format!("x{}", REPLAY_SPEEDS[playback.speed])
+    };
+
+    for mut text in &mut query {
+        **text = format!(
+            "Replay {} - P play/pause, N next round, Up/Down speed, Esc leave",
+            status
+        );
+    }
+}
+
+fn replay_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time: ResMut<Time<Virtual>>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if keys.just_pressed(KeyCode::KeyP) {
+        if time.is_paused() {
+            time.unpause();
+        } else {
+            time.pause();
+        }
+    }
+
+    // Plays until the next round, then pauses again
+    if keys.just_pressed(KeyCode::KeyN) {
+        playback.step = true;
+        time.unpause();
+    }
+
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        playback.speed = (playback.speed + 1).min(REPLAY_SPEEDS.len() - 1);
+    }
+
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        playback.speed = playback.speed.saturating_sub(1);
+    }
+
+    time.set_relative_speed(REPLAY_SPEEDS[playback.speed]);
+
+    if keys.just_pressed(KeyCode::Escape) {
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+fn pause_after_step(mut playback: ResMut<ReplayPlayback>, mut time: ResMut<Time<Virtual>>) {
+    if playback.step {
+        playback.step = false;
+        time.pause();
+    }
+}
+
+fn reset_replayed_choices(mut playback: ResMut<ReplayPlayback>) {
+    playback.applied = [false; 2];
+}
+
+// Each choice shows up when it was made in the recorded match
+fn play_replayed_choices(
+    mut playback: ResMut<ReplayPlayback>,
+    round_counter: Res<RoundCounter>,
+    betting_timer: Res<BettingTimer>,
+    mut query: Query<(&mut PlayerState, &Bullets, &Dodges, &Player), With<ReplayedPlayer>>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+) {
+    let Some(entry) = playback.replay.round(round_counter.0).cloned() else {
+        return;
+    };
+
+    for (mut player_state, bullets, dodges, player) in &mut query {
+        let index = (player.value - 1) as usize;
+        let choice = entry.choices[index];
+
+        // Choices made as the betting closed wait for the end of the phase
+        if playback.applied[index]
+            || choice
+                .at
+                .is_none_or(|at| at > betting_timer.0.elapsed_secs())
+        {
+            continue;
+        }
+
+        playback.applied[index] = true;
+        player_state.request(PlayerStates::from(choice.choice), bullets, dodges);
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+}
+
+// Whatever the frame timing, the round ends with the recorded choices
+fn settle_replayed_choices(
+    playback: Res<ReplayPlayback>,
+    round_counter: Res<RoundCounter>,
+    mut query: Query<(&mut PlayerState, &Bullets, &Dodges, &Player), With<ReplayedPlayer>>,
+) {
+    let Some(entry) = playback.replay.round(round_counter.0) else {
+        return;
+    };
+
+    for (mut player_state, bullets, dodges, player) in &mut query {
+        let choice = entry.choices[(player.value - 1) as usize];
+
+        player_state.request(PlayerStates::from(choice.choice), bullets, dodges);
+    }
+}
+
+fn replay_modifier(
+    playback: Res<ReplayPlayback>,
+    round_counter: Res<RoundCounter>,
+    mut active_modifier: ResMut<ActiveModifier>,
+    mut ev_alert: EventWriter<AlertEvent>,
+) {
+    active_modifier.0 = playback
+        .replay
+        .round(round_counter.0)
+        .and_then(|entry| entry.modifier);
+
+    if let Some(modifier) = active_modifier.0 {
+        ev_alert.send(AlertEvent {
+            value: format!("{}!", modifier.label()),
+        });
+    }
+}
+
+fn reset_pause_timer(mut playback: ResMut<ReplayPlayback>) {
+    playback.pause.reset();
+}
+
+// Nobody is there to press space between rounds
+fn skip_preparing(
+    time: Res<Time>,
+    mut playback: ResMut<ReplayPlayback>,
+    play_state: Res<State<PlayStates>>,
+    mut next_play_state: ResMut<NextState<PlayStates>>,
+) {
+    playback.pause.tick(time.delta());
+
+    if playback.pause.just_finished() {
+        next_play_state.set(play_state.get().next());
+    }
+}
+
+fn close_replay(
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut settings: ResMut<MatchSettings>,
+    mut rule_set: ResMut<RuleSet>,
+    mut time: ResMut<Time<Virtual>>,
+    mut commands: Commands,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    *settings = std::mem::take(&mut playback.saved_settings);
+    *rule_set = playback.saved_rules;
+
+    time.unpause();
+    time.set_relative_speed(1.);
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::Menu), spawn_replay_hint);
+
+    app.add_systems(
+        Update,
+        open_last_replay
+            .run_if(in_state(AppStates::Menu))
+            .run_if(not(resource_exists::<NetSession>)),
+    );
+
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        (
+            setup_replayed_fighters.after(spawn_players),
+            spawn_replay_text,
+        )
+            .run_if(replaying),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Betting),
+        reset_replayed_choices.run_if(replaying),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Preparing),
+        (reset_pause_timer, pause_after_step).run_if(replaying),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::GameOver),
+        pause_after_step.run_if(replaying),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Preparing),
+        replay_modifier.run_if(replaying),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        settle_replayed_choices
+            .before(audience_voting::forbid_dodges)
+            .before(betting::add_buffes)
+            .run_if(replaying),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            replay_controls,
+            replay_text_update,
+            play_replayed_choices.run_if(in_state(PlayStates::Betting)),
+            skip_preparing.run_if(in_state(PlayStates::Preparing)),
+        )
+            .run_if(replaying)
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(OnExit(AppStates::InGame), close_replay);
+}
@@ -13,138 +13,54 @@ fn prepare_player_for_next_round(
 }
 
 fn is_not_game_over(game_over: Res<GameOver>) -> bool {
-    !game_over.0
+    game_over.0.is_none()
 }
 
-fn check_if_dead(
+fn check_if_game_over(
     mut ev_game_over: EventWriter<GameOverEvent>,
-    mut query: Query<(&Health, &Player)>,
-    mut game_over: ResMut<GameOver>,
-) {
-    let mut dead = [false, false];
-
-    for (health, player) in &mut query {
-        if health.value == 0 {
-            let index: usize = (player.value - 1).into();
-            dead[index] = true
-        }
-    }
-
-    match dead {
-        [true, true] => {
-            ev_game_over.send(GameOverEvent {
-                player: None,
-                state: GameOvers::Tie,
-            });
-            game_over.0 = true;
-        }
-        [true, false] => {
-            ev_game_over.send(GameOverEvent {
-                player: Some(2),
-                state: GameOvers::Winner,
-            });
-            game_over.0 = true;
-        }
-        [false, true] => {
-            ev_game_over.send(GameOverEvent {
-                player: Some(1),
-                state: GameOvers::Winner,
-            });
-            game_over.0 = true;
-        }
-        [false, false] => {}
-    }
-}
-
-fn check_if_out_of_ammo(
-    mut ev_game_over: EventWriter<GameOverEvent>,
-    mut query: Query<(&Bullets, &Health, &Player)>,
-    mut game_over: ResMut<GameOver>,
-) {
-    let mut query_mut = query.iter_combinations_mut();
-    while let Some([(bullets_0, health_0, player_0), (bullets_1, health_1, player_1)]) =
-        query_mut.fetch_next()
-    {
-        let ammo = [bullets_0.value, bullets_1.value];
-
-        if ammo.iter().all(|&x| x == 0) {
-            game_over.0 = true;
-
-            let winner: Option<u8>;
-
-            match health_0.value < health_1.value {
-                true => winner = Some(player_1.value),
-                false => {
-                    if health_0.value == health_1.value {
-                        winner = None;
-                    } else {
-                        winner = Some(player_0.value)
-                    }
-                }
-            }
-
-            if winner.is_some() {
-                ev_game_over.send(GameOverEvent {
-                    player: winner,
-                    state: GameOvers::Winner,
-                });
-            } else {
-                ev_game_over.send(GameOverEvent {
-                    player: None,
-                    state: GameOvers::Tie,
-                });
-            }
-        }
-    }
-}
-
-fn check_if_no_more_rounds(
-    mut ev_game_over: EventWriter<GameOverEvent>,
-    mut query: Query<(&Health, &Player)>,
+    query: Query<(&Health, &Bullets, &Player)>,
     mut game_over: ResMut<GameOver>,
     round: Res<RoundCounter>,
+    rule_set: Res<RuleSet>,
 ) {
-    if round.0 == N_MAX_ROUND {
-        game_over.0 = true;
-
-        let mut winner: Option<u8>;
-
-        let mut query_mut = query.iter_combinations_mut();
-        while let Some([(health_0, player_0), (health_1, player_1)]) = query_mut.fetch_next() {
-            match health_0.value < health_1.value {
-                true => winner = Some(player_1.value),
-                false => {
-                    if health_0.value == health_1.value {
-                        winner = None;
-                    } else {
-                        winner = Some(player_0.value)
-                    }
-                }
-            }
+    let mut healths = [0; 2];
+    let mut bullets = [0; 2];
 
-            if winner.is_some() {
-                ev_game_over.send(GameOverEvent {
-                    player: winner,
-                    state: GameOvers::Winner,
-                });
-            } else {
-                ev_game_over.send(GameOverEvent {
-                    player: None,
-                    state: GameOvers::Tie,
-                });
-            }
-        }
+    for (health, player_bullets, player) in &query {
+        let index: usize = (player.value - 1).into();
+        healths[index] = health.value;
+        bullets[index] = player_bullets.value;
     }
+
+    let Some(result) = rule_set.match_result(healths, bullets, round.0) else {
+        return;
+    };
+
+    let event = match result {
+        MatchResult::Winner(player) => GameOverEvent {
+            player: Some(player),
+            state: GameOvers::Winner,
+        },
+        MatchResult::Tie => GameOverEvent {
+            player: None,
+            state: GameOvers::Tie,
+        },
+    };
+
+    game_over.0 = Some(event);
+    ev_game_over.send(event);
 }
 
-fn restore_dodge(
+pub(super) fn restore_dodge(
     round_counter: Res<RoundCounter>,
     mut query: Query<(&mut Dodges, &Luck, &Player), With<Player>>,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    rule_set: Res<RuleSet>,
+    mut duel_rng: ResMut<DuelRng>,
 ) {
-    if round_counter.0 % 2 == 0 {
+    if rules::is_restoring_round(round_counter.0) {
         for (mut dodges, luck, player) in &mut query {
-            if luck.value.roll() >= 25 {
+            if rule_set.restores(luck.value, duel_rng.player(player.value)) {
                 dodges.value += 1;
                 ev_tick_player.send(TickPlayerEvent {
                     player: player.value,
@@ -160,14 +76,16 @@ fn restore_dodge(
     }
 }
 
-fn restore_bullet(
+pub(super) fn restore_bullet(
     round_counter: Res<RoundCounter>,
     mut query: Query<(&mut Bullets, &Luck, &Player), With<Player>>,
     mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    rule_set: Res<RuleSet>,
+    mut duel_rng: ResMut<DuelRng>,
 ) {
-    if round_counter.0 % 2 == 0 {
+    if rules::is_restoring_round(round_counter.0) {
         for (mut bullets, luck, player) in &mut query {
-            if luck.value.roll() >= 25 {
+            if rule_set.restores(luck.value, duel_rng.player(player.value)) {
                 bullets.value += 1;
                 ev_tick_player.send(TickPlayerEvent {
                     player: player.value,
@@ -201,9 +119,7 @@ pub fn plugin(app: &mut App) {
     app.add_systems(
         OnEnter(PlayStates::RoundingUp),
         (
-            check_if_dead,
-            check_if_no_more_rounds.run_if(is_not_game_over),
-            check_if_out_of_ammo.run_if(is_not_game_over),
+            check_if_game_over,
             restore_bullet.run_if(is_not_game_over),
             restore_dodge.run_if(is_not_game_over),
         )
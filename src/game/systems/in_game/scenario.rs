@@ -0,0 +1,180 @@
+// SCENARIO MODE
+use super::*;
+
+fn scenario_enabled(settings: Res<MatchSettings>) -> bool {
+    settings.scenario.is_some()
+}
+
+// Read by `seed_duel_rng`, so the scenario rolls the dice it was checked with
+fn seed_scenario(mut settings: ResMut<MatchSettings>, scenarios: Res<Assets<Scenario>>) {
+    settings.seed = settings
+        .scenario
+        .as_ref()
+        .and_then(|handle| scenarios.get(handle))
+        .map(|scenario| scenario.seed);
+}
+
+fn forget_scenario_seed(mut settings: ResMut<MatchSettings>) {
+    settings.seed = None;
+}
+
+fn setup_scenario(
+    mut commands: Commands,
+    settings: Res<MatchSettings>,
+    scenarios: Res<Assets<Scenario>>,
+    mut round_counter: ResMut<RoundCounter>,
+    mut query: Query<(
+        Entity,
+        &Player,
+        &mut Health,
+        &mut Bullets,
+        &mut Dodges,
+        &mut Buff,
+    )>,
+) {
+    let Some(scenario) = settings
+        .scenario
+        .as_ref()
+        .and_then(|handle| scenarios.get(handle))
+    else {
+        return;
+    };
+
+    round_counter.0 = scenario.round;
+
+    for (entity, player, mut health, mut bullets, mut dodges, mut buff) in &mut query {
+        let setup = scenario.players[(player.value - 1) as usize];
+
+        health.value = setup.health;
+        health.max = setup.health.max(DEFAULT_HEALTH);
+        bullets.value = setup.bullets;
+        dodges.value = setup.dodges;
+        buff.value = setup.buff;
+
+        if player.value == 2 {
+            commands
+                .entity(entity)
+                .remove::<(KeyAssignment, AiController, ExternalBot)>()
+                .insert(ScriptedActions {
+                    first_round: scenario.round,
+                    actions: scenario.opponent.clone(),
+                });
+        }
+    }
+}
+
+fn spawn_scenario_text(
+    mut commands: Commands,
+    settings: Res<MatchSettings>,
+    scenarios: Res<Assets<Scenario>>,
+    window: Single<&Window>,
+) {
+    let Some(scenario) = settings
+        .scenario
+        .as_ref()
+        .and_then(|handle| scenarios.get(handle))
+    else {
+        return;
+    };
+
+    commands.spawn((
+        Node {
+            width: Val::Px(window.width() / 4.),
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new(format!("{}\n{}", scenario.name, scenario.description)),
+        TextFont {
+            font_size: DEFAULT_FONT_SIZE * 0.15,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(JustifyText::Left),
+        ScenarioText,
+        InGameEntity,
+    ));
+}
+
+pub(super) fn play_scripted_actions(
+    round_counter: Res<RoundCounter>,
+    mut query: Query<(
+        &ScriptedActions,
+        &mut PlayerState,
+        &Bullets,
+        &Dodges,
+        &Player,
+    )>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+) {
+    for (scripted_actions, mut player_state, bullets, dodges, player) in &mut query {
+        player_state.request(scripted_actions.action(round_counter.0), bullets, dodges);
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+}
+
+fn spawn_scenario_result_text(
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut commands: Commands,
+    window: Single<&Window>,
+) {
+    for ev in ev_game_over.read() {
+        let result = match (ev.state, ev.player) {
+            (GameOvers::Winner, Some(1)) => "Puzzle solved!",
+            _ => "Puzzle failed...",
+        };
+
+        commands.spawn((
+            Node {
+                width: Val::Px(window.width()),
+                position_type: PositionType::Absolute,
+                top: Val::Px(DEFAULT_MARGIN * 3.),
+                ..default()
+            },
+            Text::new(result),
+            TextFont {
+                font_size: DEFAULT_FONT_SIZE * 0.5,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(JustifyText::Center),
+            GlobalZIndex(2),
+            InGameEntity,
+        ));
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        (
+            seed_scenario.before(seed_duel_rng),
+            setup_scenario.after(spawn_players),
+            spawn_scenario_text,
+        )
+            .run_if(scenario_enabled),
+    );
+
+    app.add_systems(
+        OnExit(AppStates::InGame),
+        forget_scenario_seed.run_if(scenario_enabled),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        play_scripted_actions
+            .before(betting::add_buffes)
+            .run_if(scenario_enabled),
+    );
+
+    app.add_systems(
+        Update,
+        spawn_scenario_result_text
+            .run_if(scenario_enabled)
+            .run_if(in_state(AppStates::InGame)),
+    );
+}
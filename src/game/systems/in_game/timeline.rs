@@ -0,0 +1,183 @@
+// MATCH TIMELINE EXPORT
+use super::*;
+
+// Demos and replays are not worth exporting
+fn exporting(attract_mode: Res<AttractMode>, playback: Option<Res<ReplayPlayback>>) -> bool {
+    !attract_mode.active && playback.is_none()
+}
+
+fn reset_timeline(mut timeline: ResMut<MatchTimeline>) {
+    *timeline = MatchTimeline::default();
+}
+
+fn resources_of(health: &Health, bullets: &Bullets, dodges: &Dodges) -> TimelineResources {
+    TimelineResources {
+        health: health.value,
+        bullets: bullets.value,
+        dodges: dodges.value,
+    }
+}
+
+fn record_bets(
+    mut timeline: ResMut<MatchTimeline>,
+    round_counter: Res<RoundCounter>,
+    active_modifier: Res<ActiveModifier>,
+    query: Query<(&PlayerState, &Health, &Bullets, &Dodges, &Player)>,
+) {
+    let mut players = [1, 2].map(|player| TimelinePlayer {
+        player,
+        choice: Moves::Idle,
+        state: None,
+        buff: None,
+        depleted: vec![],
+        before: TimelineResources::default(),
+        after: None,
+    });
+
+    for (player_state, health, bullets, dodges, player) in &query {
+        let entry = &mut players[(player.value - 1) as usize];
+
+        entry.choice = Moves::from(player_state.0);
+        entry.before = resources_of(health, bullets, dodges);
+    }
+
+    timeline.rounds.push(TimelineRound {
+        round: round_counter.0,
+        modifier: active_modifier.0,
+        players,
+        shots: vec![],
+    });
+}
+
+// Buffs are rolled and have forced their moves by now
+fn record_buffs(mut timeline: ResMut<MatchTimeline>, query: Query<(&PlayerState, &Buff, &Player)>) {
+    let Some(round) = timeline.rounds.last_mut() else {
+        return;
+    };
+
+    for (player_state, buff, player) in &query {
+        let entry = &mut round.players[(player.value - 1) as usize];
+
+        entry.state = Some(player_state.0);
+        entry.buff = buff.value;
+    }
+}
+
+fn record_shots(
+    mut timeline: ResMut<MatchTimeline>,
+    mut ev_shot_rolled: EventReader<ShotRolledEvent>,
+    mut ev_dodged: EventReader<DodgedEvent>,
+    mut ev_depleted: EventReader<DepletedEvent>,
+    query: Query<(&Damage, &Player)>,
+) {
+    let Some(round) = timeline.rounds.last_mut() else {
+        return;
+    };
+
+    for ev in ev_shot_rolled.read() {
+        round.shots.push(TimelineShot {
+            attacker: ev.attacker,
+            target: ev.player,
+            damage: ev.damage,
+            roll: Some(ev.roll),
+            outcome: if ev.roll.hits() {
+                ShotOutcomes::Hit
+            } else {
+                ShotOutcomes::Missed
+            },
+        });
+    }
+
+    for ev in ev_dodged.read() {
+        let attacker = 3 - ev.player;
+        let damage = query
+            .iter()
+            .find(|(_, player)| player.value == attacker)
+            .map_or(0, |(damage, _)| damage.value);
+
+        round.shots.push(TimelineShot {
+            attacker,
+            target: ev.player,
+            damage,
+            roll: None,
+            outcome: ShotOutcomes::Dodged,
+        });
+    }
+
+    for ev in ev_depleted.read() {
+        round.players[(ev.player - 1) as usize]
+            .depleted
+            .push(ev.source);
+    }
+}
+
+fn record_resources(
+    mut timeline: ResMut<MatchTimeline>,
+    query: Query<(&Health, &Bullets, &Dodges, &Player)>,
+) {
+    let Some(round) = timeline.rounds.last_mut() else {
+        return;
+    };
+
+    for (health, bullets, dodges, player) in &query {
+        round.players[(player.value - 1) as usize].after =
+            Some(resources_of(health, bullets, dodges));
+    }
+}
+
+// The game over event can arrive after the phase changed, the resource can't
+fn save_timeline(mut timeline: ResMut<MatchTimeline>, game_over: Res<GameOver>) {
+    if timeline.rounds.is_empty() {
+        return;
+    }
+
+    timeline.result = game_over.0.map(|ev| TimelineResult {
+        result: ev.state,
+        winner: ev.player,
+    });
+
+    match timeline.save() {
+        Ok(path) => info!("Match timeline saved to {}", path.display()),
+        Err(error) => warn!("Could not save match timeline: {}", error),
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::InGame), reset_timeline);
+
+    // Same moment the replay records its choices
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        record_bets
+            .after(scenario::play_scripted_actions)
+            .after(audience_voting::forbid_dodges)
+            .before(betting::add_buffes)
+            .run_if(exporting),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Fighting),
+        record_buffs.run_if(exporting),
+    );
+
+    app.add_systems(
+        Update,
+        record_shots
+            .run_if(exporting)
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    // Restores are skipped once the game is over
+    app.add_systems(
+        OnEnter(PlayStates::RoundingUp),
+        record_resources
+            .after(rounding_up::restore_bullet)
+            .after(rounding_up::restore_dodge)
+            .run_if(exporting),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::GameOver),
+        save_timeline.run_if(exporting),
+    );
+}
@@ -0,0 +1,207 @@
+// WIN METER
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool};
+use rand::rngs::StdRng;
+
+use super::*;
+
+const SEGMENT_COLORS: [Color; 3] = [
+    Color::srgb(0.85, 0.25, 0.25),
+    Color::srgb(0.5, 0.5, 0.5),
+    Color::srgb(0.25, 0.45, 0.85),
+];
+
+fn win_meter_enabled(settings: Res<MatchSettings>) -> bool {
+    settings.win_meter
+}
+
+// Plays out the current position from the players' components, on the
+// compute pool since thousands of duels don't fit in a frame
+fn estimate_win_chances(
+    mut win_estimate: ResMut<WinEstimate>,
+    round: Res<RoundCounter>,
+    rule_set: Res<RuleSet>,
+    duel_rng: Res<DuelRng>,
+    query: Query<(&Health, &Bullets, &Dodges, &Luck, &Marksmanship, &Player)>,
+) {
+    let mut duel = Duel::new(*rule_set);
+    duel.round = round.0;
+
+    for (health, bullets, dodges, luck, marksmanship, player) in &query {
+        let fighter = &mut duel.fighters[(player.value - 1) as usize];
+
+        fighter.health = health.value;
+        fighter.max_health = health.max;
+        fighter.bullets = bullets.value;
+        fighter.dodges = dodges.value;
+        fighter.luck = luck.base;
+        fighter.base_luck = luck.base;
+        fighter.marksmanship = marksmanship.base;
+        fighter.base_marksmanship = marksmanship.base;
+    }
+
+    // Seeded from the match, replays show the same estimates
+    let mut rng = StdRng::seed_from_u64(duel_rng.seed.wrapping_add(round.0.into()));
+
+    win_estimate.round = round.0;
+    win_estimate.task = Some(
+        AsyncComputeTaskPool::get()
+            .spawn(async move { duel.win_chances(WIN_ESTIMATE_SAMPLES, &mut rng) }),
+    );
+}
+
+fn poll_win_estimate(
+    mut win_estimate: ResMut<WinEstimate>,
+    mut match_history: ResMut<MatchHistory>,
+) {
+    let Some(task) = win_estimate.task.as_mut() else {
+        return;
+    };
+
+    let Some(win_chances) = block_on(future::poll_once(task)) else {
+        return;
+    };
+
+    let round = win_estimate.round;
+    win_estimate.task = None;
+
+    if let Some(entry) = match_history
+        .rounds
+        .iter_mut()
+        .rev()
+        .find(|entry| entry.round == round)
+    {
+        entry.win_chances = Some(win_chances);
+    }
+}
+
+fn cancel_win_estimate(mut win_estimate: ResMut<WinEstimate>) {
+    win_estimate.task = None;
+}
+
+// Only the first round reaches the countdown without an estimate
+fn missing_estimate(match_history: Res<MatchHistory>, win_estimate: Res<WinEstimate>) -> bool {
+    win_estimate.task.is_none()
+        && match_history
+            .rounds
+            .last()
+            .is_some_and(|entry| entry.win_chances.is_none())
+}
+
+fn spawn_win_meter(mut commands: Commands, query: Query<&Window>) {
+    let window = query.single();
+    let dimensions = [300., 10.];
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Px(dimensions[0]),
+                height: Val::Px(dimensions[1]),
+                position_type: PositionType::Absolute,
+                top: Val::Px(130.),
+                left: Val::Px(window.width() / 2. - (dimensions[0] / 2.)),
+                ..default()
+            },
+            BackgroundColor(SEGMENT_COLORS[1]),
+            InGameEntity,
+        ))
+        .with_children(|parent| {
+            for (index, color) in SEGMENT_COLORS.into_iter().enumerate() {
+                parent.spawn((
+                    Node {
+                        width: Val::Percent(100. / 3.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                    WinMeterSegment { index },
+                ));
+            }
+        });
+
+    commands.spawn((
+        Node {
+            width: Val::Px(dimensions[0]),
+            position_type: PositionType::Absolute,
+            top: Val::Px(130. + dimensions[1] + 5.),
+            left: Val::Px(window.width() / 2. - (dimensions[0] / 2.)),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 15.,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(JustifyText::Center),
+        WinMeterText,
+        InGameEntity,
+    ));
+}
+
+fn win_meter_update(
+    match_history: Res<MatchHistory>,
+    mut query_segments: Query<(&mut Node, &WinMeterSegment)>,
+    mut query_text: Query<&mut Text, With<WinMeterText>>,
+) {
+    let Some(win_chances) = match_history
+        .rounds
+        .last()
+        .and_then(|entry| entry.win_chances)
+    else {
+        return;
+    };
+
+    let shares = [
+        win_chances.players[0],
+        win_chances.tie,
+        win_chances.players[1],
+    ];
+
+    for (mut node, segment) in &mut query_segments {
+        node.width = Val::Percent(shares[segment.index] * 100.);
+    }
+
+    for mut text in &mut query_text {
+        **text = format!(
+            "P1 {:.0}% | tie {:.0}% | P2 {:.0}%",
+            shares[0] * 100.,
+            shares[1] * 100.,
+            shares[2] * 100.
+        );
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::InGame),
+        spawn_win_meter.run_if(win_meter_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Preparing),
+        estimate_win_chances
+            .after(match_history::record_round_start)
+            .run_if(win_meter_enabled),
+    );
+
+    app.add_systems(
+        OnEnter(PlayStates::Countdown),
+        estimate_win_chances
+            .after(match_history::record_round_start)
+            .run_if(win_meter_enabled)
+            .run_if(missing_estimate),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            poll_win_estimate,
+            win_meter_update.run_if(resource_changed::<MatchHistory>),
+        )
+            .chain()
+            .run_if(win_meter_enabled)
+            .run_if(in_state(AppStates::InGame)),
+    );
+
+    app.add_systems(OnExit(AppStates::InGame), cancel_win_estimate);
+}
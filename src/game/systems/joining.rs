@@ -0,0 +1,243 @@
+// PLAYING ON A DEDICATED SERVER
+use super::*;
+
+fn start_joining(mut next_app_state: ResMut<NextState<AppStates>>) {
+    next_app_state.set(AppStates::Watching);
+}
+
+fn spawn_server_status_text(mut commands: Commands, connection: Res<ServerConnection>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new(format!("Connected to {}, Esc to leave", connection.address)),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::srgba(1., 1., 1., 0.5)),
+        GlobalZIndex(1),
+        ServerStatusText,
+        InGameEntity,
+    ));
+}
+
+fn seat_keys(player: u8) -> KeyAssignment {
+    KeyAssignment(PLAYER_KEY_ASSIGNMENTS[(player - 1) as usize])
+}
+
+type JoinedStats<'a> = (
+    &'a mut Health,
+    &'a mut Bullets,
+    &'a mut Dodges,
+    &'a mut PlayerState,
+    &'a Player,
+);
+
+type ServerStatusFilter = (
+    With<ServerStatusText>,
+    Without<PlayStateText>,
+    Without<RoundNumberText>,
+);
+
+// The server speaks from the seat's point of view, `me` and `opponent`
+fn seat_index(connection: &ServerConnection, player: &Player) -> usize {
+    match connection.player {
+        Some(seat) if seat != player.value => 1,
+        _ => 0,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_server(
+    mut connection: ResMut<ServerConnection>,
+    mut query: Query<JoinedStats>,
+    mut query_status: Query<&mut Text, ServerStatusFilter>,
+    mut query_play_state: Query<&mut Text, (With<PlayStateText>, Without<RoundNumberText>)>,
+    mut query_round: Query<&mut Text, (With<RoundNumberText>, Without<PlayStateText>)>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut ev_alert: EventWriter<AlertEvent>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    let Some(messages) = connection.receive() else {
+        warn!("The server on {} closed the connection", connection.address);
+        next_app_state.set(AppStates::Menu);
+        return;
+    };
+
+    for message in messages {
+        match message {
+            ServerMessage::Waiting => {
+                for mut text in &mut query_status {
+                    **text = "Waiting for an opponent, Esc to leave".into();
+                }
+            }
+            ServerMessage::MatchStart { match_id, player } => {
+                connection.player = Some(player);
+
+                let keys = seat_keys(player).0;
+
+                for mut text in &mut query_status {
+                    **text = format!(
+                        "Match {}, you are player {}: {:?} attack, {:?} dodge, {:?} buff",
+                        match_id, player, keys[0], keys[1], keys[2]
+                    );
+                }
+
+                ev_alert.send(AlertEvent {
+                    value: format!("You are player {}", player),
+                });
+            }
+            ServerMessage::Bet(request) => {
+                for (mut health, mut bullets, mut dodges, mut player_state, player) in &mut query {
+                    let view = [request.me, request.opponent][seat_index(&connection, player)];
+
+                    health.value = view.health;
+                    bullets.value = view.bullets;
+                    dodges.value = view.dodges;
+                    player_state.0 = PlayerStates::Idle;
+
+                    ev_change_player_state.send(PlayerStateChangeEvent {
+                        player: player.value,
+                    });
+                }
+
+                for mut text in &mut query_round {
+                    **text = format!("Round {}/{}", request.round, request.max_round);
+                }
+
+                for mut text in &mut query_play_state {
+                    **text = format!("{:?}", PlayStates::Betting);
+                }
+
+                connection.bet_timer =
+                    Some(Timer::from_seconds(request.time_limit, TimerMode::Once));
+            }
+            ServerMessage::RoundResult {
+                outcome, health, ..
+            } => {
+                connection.bet_timer = None;
+
+                for (mut player_health, _, _, mut player_state, player) in &mut query {
+                    let index = seat_index(&connection, player);
+                    let (choice, damage) = [
+                        (outcome.me, outcome.damage_taken),
+                        (outcome.opponent, outcome.damage_dealt),
+                    ][index];
+
+                    player_state.0 = PlayerStates::from(choice);
+                    player_health.value = health[(player.value - 1) as usize];
+
+                    ev_change_player_state.send(PlayerStateChangeEvent {
+                        player: player.value,
+                    });
+
+                    if damage > 0 {
+                        ev_tick_player.send(TickPlayerEvent {
+                            player: player.value,
+                            value: format!("-{} HP", damage),
+                        });
+                    }
+                }
+
+                for mut text in &mut query_play_state {
+                    **text = format!("{:?}", PlayStates::Fighting);
+                }
+            }
+            ServerMessage::OpponentLeft => {
+                ev_alert.send(AlertEvent {
+                    value: "Your opponent left".into(),
+                });
+            }
+            ServerMessage::GameOver { winner, .. } => {
+                connection.bet_timer = None;
+
+                ev_alert.send(AlertEvent {
+                    value: match winner {
+                        Some(winner) if Some(winner) == connection.player => "You win!".into(),
+                        Some(winner) => format!("Player {} wins!", winner),
+                        None => "It's a tie!".into(),
+                    },
+                });
+
+                for mut text in &mut query_play_state {
+                    **text = format!("{:?}", PlayStates::GameOver);
+                }
+            }
+        }
+    }
+}
+
+// First key of the seat sends the bet, there is no changing one's mind
+fn bet_on_server(
+    mut connection: ResMut<ServerConnection>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+) {
+    let Some(player) = connection.player else {
+        return;
+    };
+    let Some(timer) = connection.bet_timer.as_mut() else {
+        return;
+    };
+
+    if timer.tick(time.delta()).finished() {
+        connection.bet_timer = None;
+        return;
+    }
+
+    let seat_keys = seat_keys(player);
+    let Some(state) = keys
+        .get_just_pressed()
+        .find_map(|key| seat_keys.derive_player_state(key))
+    else {
+        return;
+    };
+
+    connection.bet_timer = None;
+
+    if !connection.send_move(Moves::from(state)) {
+        return;
+    }
+
+    ev_tick_player.send(TickPlayerEvent {
+        player,
+        value: "Locked".into(),
+    });
+}
+
+fn forget_server(mut commands: Commands) {
+    commands.remove_resource::<ServerConnection>();
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        start_joining
+            .run_if(in_state(AppStates::Menu))
+            .run_if(resource_exists::<ServerConnection>),
+    );
+
+    app.add_systems(
+        OnEnter(AppStates::Watching),
+        spawn_server_status_text.run_if(resource_exists::<ServerConnection>),
+    );
+
+    app.add_systems(
+        Update,
+        (follow_server, bet_on_server)
+            .chain()
+            .run_if(resource_exists::<ServerConnection>)
+            .run_if(in_state(AppStates::Watching)),
+    );
+
+    app.add_systems(
+        OnExit(AppStates::Watching),
+        forget_server.run_if(resource_exists::<ServerConnection>),
+    );
+}
@@ -1,4 +1,7 @@
+use std::fs;
+
 use super::*;
+use bevy::asset::io::file::FileAssetReader;
 use bevy::asset::LoadState;
 use bevy::asset::UntypedAssetId;
 
@@ -174,19 +177,161 @@ fn load_bg_textures(
     hand.sprite = Some(bg_sprite_handle);
 }
 
+// DATA FOLDERS
+
+// Loaded file by file, a folder would fail as a whole on one broken file
+fn load_data_files<A: Asset>(
+    asset_server: &AssetServer,
+    loading: &mut DataFilesLoading,
+    folder: &str,
+    extension: &str,
+) -> Vec<Handle<A>> {
+    let folder_path = FileAssetReader::get_base_path()
+        .join(ASSETS_DIR)
+        .join(folder);
+
+    let Ok(entries) = fs::read_dir(folder_path) else {
+        return vec![];
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(extension))
+        .collect();
+
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let handle = asset_server.load(format!("{}/{}", folder, name));
+
+            loading.0.push(handle.clone().untyped());
+
+            handle
+        })
+        .collect()
+}
+
+// Drops the files that failed to load or to validate
+fn loaded_data_files<A: Asset>(
+    asset_server: &AssetServer,
+    assets: &Assets<A>,
+    handles: &mut Vec<Handle<A>>,
+    validate: impl Fn(&A) -> Result<(), String>,
+) {
+    handles.retain(|handle| {
+        let path = asset_server
+            .get_path(handle.id())
+            .map(|path| path.to_string())
+            .unwrap_or_default();
+
+        match assets.get(handle).map(&validate) {
+            Some(Ok(())) => true,
+            Some(Err(error)) => {
+                warn!("Skipping {}: {}", path, error);
+                false
+            }
+            None => {
+                warn!("Skipping {}: it could not be loaded", path);
+                false
+            }
+        }
+    });
+}
+
+fn load_scenarios(
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<ScenarioLibrary>,
+    mut loading: ResMut<DataFilesLoading>,
+) {
+    library.scenarios = load_data_files(&asset_server, &mut loading, "scenarios", ".scenario.json");
+}
+
+fn load_personalities(
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<PersonalityLibrary>,
+    mut loading: ResMut<DataFilesLoading>,
+) {
+    library.personalities = load_data_files(
+        &asset_server,
+        &mut loading,
+        "personalities",
+        ".personality.json",
+    );
+}
+
+fn load_external_bots(
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<ExternalBotLibrary>,
+    mut loading: ResMut<DataFilesLoading>,
+) {
+    library.bots = load_data_files(&asset_server, &mut loading, "bots", ".bot.json");
+}
+
+fn collect_scenarios(
+    asset_server: Res<AssetServer>,
+    scenarios: Res<Assets<Scenario>>,
+    rule_set: Res<RuleSet>,
+    mut library: ResMut<ScenarioLibrary>,
+) {
+    loaded_data_files(
+        &asset_server,
+        &scenarios,
+        &mut library.scenarios,
+        |scenario| scenario.validate(&rule_set),
+    );
+}
+
+fn collect_personalities(
+    asset_server: Res<AssetServer>,
+    personalities: Res<Assets<Personality>>,
+    mut library: ResMut<PersonalityLibrary>,
+) {
+    loaded_data_files(
+        &asset_server,
+        &personalities,
+        &mut library.personalities,
+        Personality::validate,
+    );
+}
+
+fn collect_external_bots(
+    asset_server: Res<AssetServer>,
+    bots: Res<Assets<ExternalBotConfig>>,
+    mut library: ResMut<ExternalBotLibrary>,
+) {
+    loaded_data_files(
+        &asset_server,
+        &bots,
+        &mut library.bots,
+        ExternalBotConfig::validate,
+    );
+}
+
 // GENERICS
 
 fn check_assets_loaded(
     server: Res<AssetServer>,
     mut loading: ResMut<AssetsLoading>,
+    mut data_files: ResMut<DataFilesLoading>,
     mut next_app_state: ResMut<NextState<AppStates>>,
 ) {
+    // Broken data files are skipped once the rest is loaded
+    let data_files_done = data_files.0.iter().all(|handle| {
+        matches!(
+            server.get_load_state(handle.id()),
+            Some(LoadState::Loaded | LoadState::Failed(_))
+        )
+    });
+
     match server.get_group_load_state(loading.0.iter().map(|h| h.id())) {
         LoadState::Failed(_) => {
             panic!("Resources failed to load")
         }
-        LoadState::Loaded => {
+        LoadState::Loaded if data_files_done => {
             loading.0 = vec![];
+            data_files.0 = vec![];
             next_app_state.set(AppStates::Menu);
         }
         _ => {}
@@ -266,6 +411,9 @@ pub fn plugin(app: &mut App) {
                 load_health_textures,
                 load_mana_textures,
                 load_stamina_textures,
+                load_scenarios,
+                load_personalities,
+                load_external_bots,
             ),
         )
             .chain(),
@@ -274,5 +422,13 @@ pub fn plugin(app: &mut App) {
         Update,
         check_assets_loaded.run_if(in_state(AppStates::Loading)),
     );
-    app.add_systems(OnExit(AppStates::Loading), clean_system::<LoadingEntity>);
+    app.add_systems(
+        OnExit(AppStates::Loading),
+        (
+            clean_system::<LoadingEntity>,
+            collect_scenarios,
+            collect_personalities,
+            collect_external_bots,
+        ),
+    );
 }
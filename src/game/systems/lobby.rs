@@ -0,0 +1,370 @@
+// LAN LOBBY
+use super::*;
+
+const LETTER_KEYS: [(KeyCode, char); 26] = [
+    (KeyCode::KeyA, 'A'),
+    (KeyCode::KeyB, 'B'),
+    (KeyCode::KeyC, 'C'),
+    (KeyCode::KeyD, 'D'),
+    (KeyCode::KeyE, 'E'),
+    (KeyCode::KeyF, 'F'),
+    (KeyCode::KeyG, 'G'),
+    (KeyCode::KeyH, 'H'),
+    (KeyCode::KeyI, 'I'),
+    (KeyCode::KeyJ, 'J'),
+    (KeyCode::KeyK, 'K'),
+    (KeyCode::KeyL, 'L'),
+    (KeyCode::KeyM, 'M'),
+    (KeyCode::KeyN, 'N'),
+    (KeyCode::KeyO, 'O'),
+    (KeyCode::KeyP, 'P'),
+    (KeyCode::KeyQ, 'Q'),
+    (KeyCode::KeyR, 'R'),
+    (KeyCode::KeyS, 'S'),
+    (KeyCode::KeyT, 'T'),
+    (KeyCode::KeyU, 'U'),
+    (KeyCode::KeyV, 'V'),
+    (KeyCode::KeyW, 'W'),
+    (KeyCode::KeyX, 'X'),
+    (KeyCode::KeyY, 'Y'),
+    (KeyCode::KeyZ, 'Z'),
+];
+
+fn browsing(session: Option<Res<NetSession>>) -> bool {
+    session.is_none()
+}
+
+fn spawn_lobby_hint(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            right: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new("Press L for the LAN lobby"),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        GlobalZIndex(1),
+        MenuEntity,
+    ));
+}
+
+fn open_lobby(keys: Res<ButtonInput<KeyCode>>, mut next_app_state: ResMut<NextState<AppStates>>) {
+    if keys.just_pressed(KeyCode::KeyL) {
+        next_app_state.set(AppStates::Lobby);
+    }
+}
+
+fn spawn_lobby_ui(mut commands: Commands, query: Query<&Window>) {
+    let window = query.single();
+
+    commands.spawn((
+        Node {
+            width: Val::Px(window.width()),
+            height: Val::Px(window.height()),
+            padding: UiRect::all(Val::Px(DEFAULT_MARGIN)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(25., 0., 255., 0.5)),
+        Text::default(),
+        TextFont::from_font_size(30.),
+        LobbyText,
+        LobbyEntity,
+    ));
+}
+
+fn open_browser(mut commands: Commands, mut lobby_input: ResMut<LobbyInput>) {
+    lobby_input.cursor = 0;
+    lobby_input.code.clear();
+
+    match LobbyBrowser::bind() {
+        Ok(browser) => commands.insert_resource(browser),
+        Err(error) => warn!("Could not listen for LAN games: {}", error),
+    }
+}
+
+fn discover_games(mut browser: ResMut<LobbyBrowser>) {
+    browser.receive();
+}
+
+fn type_room_code(keys: Res<ButtonInput<KeyCode>>, mut lobby_input: ResMut<LobbyInput>) {
+    for (key, letter) in LETTER_KEYS {
+        if keys.just_pressed(key) && lobby_input.code.len() < ROOM_CODE_LENGTH {
+            lobby_input.code.push(letter);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Backspace) {
+        lobby_input.code.pop();
+    }
+}
+
+fn move_lobby_cursor(
+    keys: Res<ButtonInput<KeyCode>>,
+    browser: Option<Res<LobbyBrowser>>,
+    mut lobby_input: ResMut<LobbyInput>,
+) {
+    let games = browser.map_or(0, |browser| browser.games.len());
+
+    if games == 0 {
+        lobby_input.cursor = 0;
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        lobby_input.cursor = (lobby_input.cursor + games - 1) % games;
+    }
+
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        lobby_input.cursor = (lobby_input.cursor + 1) % games;
+    }
+
+    lobby_input.cursor = lobby_input.cursor.min(games - 1);
+}
+
+fn join_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    browser: Option<Res<LobbyBrowser>>,
+    mut lobby_input: ResMut<LobbyInput>,
+    mut commands: Commands,
+) {
+    let Some(browser) = browser else {
+        return;
+    };
+
+    // A complete room code joins right away, Enter joins the selected game
+    let game = if lobby_input.code.len() == ROOM_CODE_LENGTH {
+        browser.find(&lobby_input.code)
+    } else if keys.just_pressed(KeyCode::Enter) {
+        browser.games.get(lobby_input.cursor)
+    } else {
+        None
+    };
+
+    let Some(game) = game else {
+        return;
+    };
+
+    match NetSession::join(&game.address.to_string()) {
+        Ok(session) => {
+            lobby_input.room = Some(game.announcement.room.clone());
+            commands.insert_resource(session);
+            commands.remove_resource::<LobbyBrowser>();
+        }
+        Err(error) => warn!("Could not join {}: {}", game.address, error),
+    }
+}
+
+fn host_lobby_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<MatchSettings>,
+    rule_set: Res<RuleSet>,
+    mut lobby_input: ResMut<LobbyInput>,
+    mut commands: Commands,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let session = match NetSession::host("0.0.0.0:0") {
+        Ok(session) => session,
+        Err(error) => {
+            warn!("Could not host a game: {}", error);
+            return;
+        }
+    };
+
+    let announcement = LobbyAnnouncement {
+        room: room_code(),
+        host_name: session.local.name.clone(),
+        port: session.address.port(),
+        rules: format!(
+            "{} HP, {} bullets, {} dodges, {} rounds, hidden choices {}",
+            rule_set.health,
+            rule_set.bullets,
+            rule_set.dodges,
+            rule_set.max_round,
+            if settings.hidden_choices { "on" } else { "off" }
+        ),
+    };
+
+    match LobbyBeacon::new(announcement) {
+        Ok(beacon) => {
+            lobby_input.room = Some(beacon.announcement.room.clone());
+            commands.insert_resource(beacon);
+            commands.insert_resource(session);
+            commands.remove_resource::<LobbyBrowser>();
+        }
+        Err(error) => warn!("Could not announce a game: {}", error),
+    }
+}
+
+// Only open games are announced
+fn announce_game(mut beacon: ResMut<LobbyBeacon>, session: Res<NetSession>) {
+    if !session.connected() {
+        beacon.announce();
+    }
+}
+
+fn toggle_ready(keys: Res<ButtonInput<KeyCode>>, mut session: ResMut<NetSession>) {
+    // The key that opened the room doesn't count
+    if keys.just_pressed(KeyCode::Space) && !session.is_added() {
+        session.local.ready = !session.local.ready;
+    }
+}
+
+fn start_when_ready(
+    mut session: ResMut<NetSession>,
+    mut settings: ResMut<MatchSettings>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    let remote_ready = session.connected() && session.remote.in_menu && session.remote.ready;
+
+    if session.role == NetRoles::Host && session.local.ready && remote_ready {
+        online::host_game(&mut session, &mut settings);
+        next_app_state.set(AppStates::InGame);
+    }
+}
+
+fn leave_lobby(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut commands: Commands,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<NetSession>();
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+fn close_lobby(mut commands: Commands) {
+    commands.remove_resource::<LobbyBrowser>();
+    commands.remove_resource::<LobbyBeacon>();
+}
+
+fn lobby_text_update(
+    browser: Option<Res<LobbyBrowser>>,
+    session: Option<Res<NetSession>>,
+    lobby_input: Res<LobbyInput>,
+    mut query: Query<&mut Text, With<LobbyText>>,
+) {
+    let mut lines = vec!["LAN lobby".to_string(), String::new()];
+
+    match session {
+        None => {
+            lines.push("Space: host a game, Enter: join, type a room code, Esc: back".into());
+            lines.push(String::new());
+
+            match browser.as_ref() {
+                None => lines.push(format!("Could not listen on port {}", LOBBY_PORT)),
+                Some(browser) if browser.games.is_empty() => {
+                    lines.push("Looking for games...".into())
+                }
+                Some(browser) => {
+                    for (index, game) in browser.games.iter().enumerate() {
+                        let prefix = if index == lobby_input.cursor {
+                            "> "
+                        } else {
+                            "  "
+                        };
+                        let announcement = &game.announcement;
+
+                        lines.push(format!(
+                            "{}{}  {}  ({})",
+                            prefix, announcement.room, announcement.host_name, announcement.rules
+                        ));
+                    }
+                }
+            }
+
+            lines.push(String::new());
+            lines.push(format!(
+                "Room code: {}{}",
+                lobby_input.code,
+                "_".repeat(ROOM_CODE_LENGTH - lobby_input.code.len())
+            ));
+        }
+        Some(session) => {
+            lines.push(format!(
+                "Room {}",
+                lobby_input.room.as_deref().unwrap_or("?")
+            ));
+            lines.push("Space: toggle ready, Esc: leave".into());
+            lines.push(String::new());
+
+            let ready = |value: bool| if value { "ready" } else { "not ready" };
+
+            for player in [1, 2] {
+                let line = if player == session.local_player() {
+                    format!(
+                        "Player {} (you, {}): {}",
+                        player,
+                        session.local.name,
+                        ready(session.local.ready)
+                    )
+                } else if session.connected() {
+                    format!(
+                        "Player {} ({}): {}",
+                        player,
+                        session.remote.name,
+                        ready(session.remote.ready && session.remote.in_menu)
+                    )
+                } else {
+                    format!("Player {}: waiting...", player)
+                };
+
+                lines.push(line);
+            }
+        }
+    }
+
+    for mut text in &mut query {
+        **text = lines.join("\n");
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(AppStates::Menu), spawn_lobby_hint);
+
+    app.add_systems(Update, open_lobby.run_if(in_state(AppStates::Menu)));
+
+    app.add_systems(
+        OnEnter(AppStates::Lobby),
+        (spawn_lobby_ui, open_browser.run_if(browsing)),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            (
+                discover_games.run_if(resource_exists::<LobbyBrowser>),
+                type_room_code,
+                move_lobby_cursor,
+                join_game,
+                host_lobby_game,
+            )
+                .chain()
+                .run_if(browsing),
+            (
+                announce_game.run_if(resource_exists::<LobbyBeacon>),
+                toggle_ready,
+                start_when_ready,
+            )
+                .chain()
+                .run_if(resource_exists::<NetSession>),
+            lobby_text_update,
+            leave_lobby,
+        )
+            .chain()
+            .run_if(in_state(AppStates::Lobby)),
+    );
+
+    app.add_systems(
+        OnExit(AppStates::Lobby),
+        (clean_system::<LobbyEntity>, close_lobby),
+    );
+}
@@ -1,4 +1,5 @@
 use super::*;
+use bevy::ecs::system::SystemParam;
 
 fn wait_for_input_to_start_game(
     keys: Res<ButtonInput<KeyCode>>,
@@ -62,17 +63,297 @@ fn spawn_start_game_ui(mut commands: Commands, query: Query<&Window>) {
         ));
 }
 
+fn spawn_match_settings_ui(mut commands: Commands, query: Query<&Window>) {
+    let window = query.single();
+
+    commands.spawn((
+        Node {
+            width: Val::Px(window.width()),
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(DEFAULT_MARGIN),
+            ..default()
+        },
+        Text::default(),
+        TextLayout::new_with_justify(JustifyText::Center),
+        TextFont::from_font_size(18.),
+        GlobalZIndex(1),
+        MatchSettingsText,
+        MenuEntity,
+    ));
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MenuOptions {
+    QuickDraw,
+    HiddenChoices,
+    Draft,
+    Handicap(usize, HandicapStats),
+    Scenario,
+    Controller(usize),
+    Profile,
+    Adaptive,
+    WinMeter,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum HandicapStats {
+    Health,
+    Bullets,
+    Luck,
+    BettingTime,
+}
+
+const MENU_OPTIONS: [MenuOptions; 17] = [
+    MenuOptions::Controller(0),
+    MenuOptions::Controller(1),
+    MenuOptions::Profile,
+    MenuOptions::Adaptive,
+    MenuOptions::QuickDraw,
+    MenuOptions::HiddenChoices,
+    MenuOptions::Draft,
+    MenuOptions::WinMeter,
+    MenuOptions::Handicap(0, HandicapStats::Health),
+    MenuOptions::Handicap(0, HandicapStats::Bullets),
+    MenuOptions::Handicap(0, HandicapStats::Luck),
+    MenuOptions::Handicap(0, HandicapStats::BettingTime),
+    MenuOptions::Handicap(1, HandicapStats::Health),
+    MenuOptions::Handicap(1, HandicapStats::Bullets),
+    MenuOptions::Handicap(1, HandicapStats::Luck),
+    MenuOptions::Handicap(1, HandicapStats::BettingTime),
+    MenuOptions::Scenario,
+];
+
+const CONTROLLERS: [Controllers; 5] = [
+    Controllers::Human,
+    Controllers::Ai(Difficulties::Easy),
+    Controllers::Ai(Difficulties::Normal),
+    Controllers::Ai(Difficulties::Hard),
+    Controllers::Learner,
+];
+
+// Bundles the data libraries some options cycle through
+#[derive(SystemParam)]
+struct MatchLibraries<'w> {
+    scenario_library: Res<'w, ScenarioLibrary>,
+    scenarios: Res<'w, Assets<Scenario>>,
+    personality_library: Res<'w, PersonalityLibrary>,
+    personalities: Res<'w, Assets<Personality>>,
+    external_bot_library: Res<'w, ExternalBotLibrary>,
+    external_bots: Res<'w, Assets<ExternalBotConfig>>,
+}
+
+impl MatchLibraries<'_> {
+    fn controllers(&self) -> Vec<Controllers> {
+        CONTROLLERS
+            .into_iter()
+            .chain((0..self.personality_library.personalities.len()).map(Controllers::Personality))
+            .chain((0..self.external_bot_library.bots.len()).map(Controllers::External))
+            .collect()
+    }
+}
+
+fn step(value: u8, max: u8, forward: bool) -> u8 {
+    if forward {
+        (value + 1) % (max + 1)
+    } else {
+        (value + max) % (max + 1)
+    }
+}
+
+// Each stat goes up in steps of its own bonus
+impl HandicapStats {
+    fn level(&self, handicap: &Handicap) -> u8 {
+        match self {
+            HandicapStats::Health => handicap.health,
+            HandicapStats::Bullets => handicap.bullets,
+            HandicapStats::Luck => handicap.luck / HANDICAP_LUCK_BONUS,
+            HandicapStats::BettingTime => {
+                (handicap.betting_time / HANDICAP_BETTING_BONUS).round() as u8
+            }
+        }
+    }
+
+    fn set_level(&self, handicap: &mut Handicap, level: u8) {
+        match self {
+            HandicapStats::Health => handicap.health = level,
+            HandicapStats::Bullets => handicap.bullets = level,
+            HandicapStats::Luck => handicap.luck = level * HANDICAP_LUCK_BONUS,
+            HandicapStats::BettingTime => {
+                handicap.betting_time = level as f32 * HANDICAP_BETTING_BONUS
+            }
+        }
+    }
+
+    fn label(&self, handicap: &Handicap) -> String {
+        match self {
+            HandicapStats::Health => format!("+{} health", handicap.health),
+            HandicapStats::Bullets => format!("+{} bullets", handicap.bullets),
+            HandicapStats::Luck => format!("+{} luck", handicap.luck),
+            HandicapStats::BettingTime => format!("+{}s betting time", handicap.betting_time),
+        }
+    }
+}
+
+impl MenuOptions {
+    fn label(&self, settings: &MatchSettings, libraries: &MatchLibraries) -> String {
+        let on_off = |value: bool| if value { "on" } else { "off" };
+
+        match self {
+            MenuOptions::QuickDraw => format!("Quick draw: {}", on_off(settings.quick_draw)),
+            MenuOptions::HiddenChoices => {
+                format!("Hidden choices: {}", on_off(settings.hidden_choices))
+            }
+            MenuOptions::Draft => format!("Stat draft: {}", on_off(settings.draft)),
+            MenuOptions::WinMeter => format!("Win meter: {}", on_off(settings.win_meter)),
+            MenuOptions::Handicap(index, stat) => format!(
+                "Player {} handicap: {}",
+                index + 1,
+                stat.label(&settings.handicaps[*index])
+            ),
+            MenuOptions::Controller(index) => match settings.controllers[*index] {
+                Controllers::Human => format!("Player {}: Human", index + 1),
+                Controllers::Ai(difficulty) => {
+                    format!("Player {}: Bot ({:?})", index + 1, difficulty)
+                }
+                Controllers::Learner => format!("Player {}: Bot (Learning)", index + 1),
+                Controllers::Personality(personality) => {
+                    let name = libraries
+                        .personality_library
+                        .personalities
+                        .get(personality)
+                        .and_then(|handle| libraries.personalities.get(handle))
+                        .map_or("Unknown", |personality| personality.name.as_str());
+
+                    format!("Player {}: Bot ({})", index + 1, name)
+                }
+                Controllers::External(bot) => {
+                    let name = libraries
+                        .external_bot_library
+                        .bots
+                        .get(bot)
+                        .and_then(|handle| libraries.external_bots.get(handle))
+                        .map_or("Unknown", |config| config.name.as_str());
+
+                    format!("Player {}: External ({})", index + 1, name)
+                }
+                Controllers::Remote => format!("Player {}: Remote", index + 1),
+                Controllers::Replay => format!("Player {}: Replay", index + 1),
+            },
+            MenuOptions::Profile => format!("Profile: {}", settings.profile + 1),
+            MenuOptions::Adaptive => format!(
+                "Adaptive difficulty: {}",
+                on_off(!settings.fixed_difficulty)
+            ),
+            MenuOptions::Scenario => match settings
+                .scenario
+                .as_ref()
+                .and_then(|handle| libraries.scenarios.get(handle))
+            {
+                Some(scenario) => format!("Scenario: {}", scenario.name),
+                None => "Scenario: off".to_string(),
+            },
+        }
+    }
+
+    fn cycle(&self, settings: &mut MatchSettings, libraries: &MatchLibraries, forward: bool) {
+        match self {
+            MenuOptions::QuickDraw => settings.quick_draw = !settings.quick_draw,
+            MenuOptions::HiddenChoices => settings.hidden_choices = !settings.hidden_choices,
+            MenuOptions::Draft => settings.draft = !settings.draft,
+            MenuOptions::WinMeter => settings.win_meter = !settings.win_meter,
+            MenuOptions::Handicap(index, stat) => {
+                let handicap = &mut settings.handicaps[*index];
+                let level = step(stat.level(handicap), N_MAX_HANDICAP, forward);
+                stat.set_level(handicap, level);
+            }
+            MenuOptions::Controller(index) => {
+                let controllers = libraries.controllers();
+                let current = controllers
+                    .iter()
+                    .position(|controller| *controller == settings.controllers[*index])
+                    .unwrap_or(0);
+                let next = step(current as u8, controllers.len() as u8 - 1, forward);
+
+                settings.controllers[*index] = controllers[next as usize];
+            }
+            MenuOptions::Adaptive => settings.fixed_difficulty = !settings.fixed_difficulty,
+            MenuOptions::Profile => {
+                settings.profile = step(settings.profile, N_PROFILES - 1, forward);
+            }
+            MenuOptions::Scenario => {
+                // Index 0 stands for no scenario
+                let library = &libraries.scenario_library;
+                let current = settings
+                    .scenario
+                    .as_ref()
+                    .and_then(|handle| library.scenarios.iter().position(|other| other == handle))
+                    .map_or(0, |position| position + 1);
+                let next = step(current as u8, library.scenarios.len() as u8, forward) as usize;
+
+                settings.scenario = next
+                    .checked_sub(1)
+                    .map(|position| library.scenarios[position].clone());
+            }
+        }
+    }
+}
+
+fn match_settings_text_update(
+    settings: Res<MatchSettings>,
+    cursor: Res<MenuCursor>,
+    libraries: MatchLibraries,
+    mut query: Query<&mut Text, With<MatchSettingsText>>,
+) {
+    for mut text in &mut query {
+        **text = MENU_OPTIONS
+            .iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let prefix = if index == cursor.0 { "> " } else { "  " };
+                format!("{}{}", prefix, option.label(&settings, &libraries))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+fn navigate_match_settings(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cursor: ResMut<MenuCursor>,
+    mut settings: ResMut<MatchSettings>,
+    libraries: MatchLibraries,
+) {
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowUp => cursor.0 = (cursor.0 + MENU_OPTIONS.len() - 1) % MENU_OPTIONS.len(),
+            KeyCode::ArrowDown => cursor.0 = (cursor.0 + 1) % MENU_OPTIONS.len(),
+            KeyCode::ArrowLeft => MENU_OPTIONS[cursor.0].cycle(&mut settings, &libraries, false),
+            KeyCode::ArrowRight => MENU_OPTIONS[cursor.0].cycle(&mut settings, &libraries, true),
+            _ => {}
+        }
+    }
+}
+
 pub fn plugin(app: &mut App) {
     app.add_systems(
         OnEnter(AppStates::Menu),
-        (spawn_menu_audio, spawn_start_game_ui),
+        (
+            spawn_menu_audio,
+            spawn_start_game_ui,
+            spawn_match_settings_ui,
+        ),
     );
 
     app.add_systems(
         Update,
         (
-            audio_react_to_input,
-            wait_for_input_to_start_game.run_if(in_state(AppStates::Menu)),
+            audio_react_to_input.run_if(not(in_state(AppStates::Watching))),
+            wait_for_input_to_start_game
+                .run_if(in_state(AppStates::Menu))
+                .run_if(not(resource_exists::<NetSession>)),
+            (navigate_match_settings, match_settings_text_update)
+                .chain()
+                .run_if(in_state(AppStates::Menu)),
         ),
     );
 
@@ -0,0 +1,277 @@
+// ONLINE DUELS
+use super::*;
+
+fn exchange_status(mut session: ResMut<NetSession>, app_state: Res<State<AppStates>>) {
+    // The lobby counts as the menu, anything but a game does
+    session.local.in_menu = *app_state.get() != AppStates::InGame;
+    session.receive();
+    session.send_status();
+}
+
+// Same rules on both machines, each one playing its own side
+fn online_settings(settings: &mut MatchSettings, session: &NetSession) {
+    let local = (session.local_player() - 1) as usize;
+
+    settings.quick_draw = false;
+    settings.draft = false;
+    settings.scenario = None;
+    settings.handicaps = default();
+    settings.controllers[local] = Controllers::Human;
+    settings.controllers[1 - local] = Controllers::Remote;
+    settings.seed = Some(session.local.seed);
+}
+
+fn host_starts_game(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut session: ResMut<NetSession>,
+    mut settings: ResMut<MatchSettings>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if !keys.just_pressed(KeyCode::Enter) || !session.connected() || !session.remote.in_menu {
+        return;
+    }
+
+    host_game(&mut session, &mut settings);
+    next_app_state.set(AppStates::InGame);
+}
+
+pub(super) fn host_game(session: &mut NetSession, settings: &mut MatchSettings) {
+    let game = session.local.game + 1;
+    session.start_game(game, rand::random());
+
+    online_settings(settings, session);
+}
+
+fn join_follows_host(
+    mut session: ResMut<NetSession>,
+    mut settings: ResMut<MatchSettings>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if !session.connected() || session.remote.in_menu || session.remote.game <= session.local.game {
+        return;
+    }
+
+    let (game, seed) = (session.remote.game, session.remote.seed);
+    session.start_game(game, seed);
+
+    online_settings(&mut settings, &session);
+    next_app_state.set(AppStates::InGame);
+}
+
+fn spawn_online_status_text(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        GlobalZIndex(1),
+        OnlineStatusText,
+        MenuEntity,
+    ));
+}
+
+fn online_status_text_update(
+    session: Res<NetSession>,
+    mut query: Query<&mut Text, With<OnlineStatusText>>,
+) {
+    let status = match (session.role, session.connected()) {
+        (NetRoles::Host, false) => {
+            format!("Hosting on {}, waiting for an opponent", session.address)
+        }
+        (NetRoles::Join, false) => format!("Joining {}, waiting for the host", session.address),
+        (NetRoles::Host, true) if !session.remote.in_menu => {
+            "Opponent connected, still on the game over screen".to_string()
+        }
+        (NetRoles::Host, true) => "Opponent connected, press Enter to start".to_string(),
+        (NetRoles::Join, true) => "Connected, waiting for the host to start".to_string(),
+    };
+
+    for mut text in &mut query {
+        **text = format!("Online as player {}: {}", session.local_player(), status);
+    }
+}
+
+// The local choice is committed once the betting timer runs out
+fn lock_choice(
+    time: Res<Time>,
+    mut betting_timer: ResMut<BettingTimer>,
+    mut session: ResMut<NetSession>,
+    round: Res<RoundCounter>,
+    query: Query<(&PlayerState, &Player)>,
+    mut commands: Commands,
+) {
+    betting_timer.0.tick(time.delta());
+
+    let local_player = session.local_player();
+
+    if !betting_timer.0.finished() || session.secrets.len() >= round.0 as usize {
+        return;
+    }
+
+    let Some((player_state, _)) = query
+        .iter()
+        .find(|(_, player)| player.value == local_player)
+    else {
+        return;
+    };
+
+    let round = round.0;
+    session.commit(round, Moves::from(player_state.0));
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(DEFAULT_MARGIN),
+            width: Val::Percent(100.),
+            ..default()
+        },
+        Text::new("Waiting for the opponent..."),
+        TextFont {
+            font_size: 30.,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        WaitingText,
+        InGameEntity,
+    ));
+}
+
+fn reveal_choice(mut session: ResMut<NetSession>, round: Res<RoundCounter>) {
+    session.reveal(round.0);
+}
+
+// Betting only ends once both choices are revealed
+fn resolve_choices(
+    session: Res<NetSession>,
+    round: Res<RoundCounter>,
+    mut query: Query<(&mut PlayerState, &Bullets, &Dodges, &Player)>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    mut next_play_state: ResMut<NextState<PlayStates>>,
+) {
+    let (Some(local_reveal), Some(Ok(remote_choice))) = (
+        session.secrets.get((round.0 - 1) as usize),
+        session.remote_choice(round.0),
+    ) else {
+        return;
+    };
+
+    let choices = match session.local_player() {
+        1 => [local_reveal.choice, remote_choice],
+        _ => [remote_choice, local_reveal.choice],
+    };
+
+    for (mut player_state, bullets, dodges, player) in &mut query {
+        player_state.request(choices[(player.value - 1) as usize].into(), bullets, dodges);
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+    }
+
+    next_play_state.set(PlayStates::Fighting);
+}
+
+// A reveal that doesn't match its commitment, or never comes, loses the match
+fn forfeit_on_bad_reveal(
+    session: Res<NetSession>,
+    round: Res<RoundCounter>,
+    mut game_over: ResMut<GameOver>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+    mut ev_alert: EventWriter<AlertEvent>,
+) {
+    if game_over.0.is_some() {
+        return;
+    }
+
+    if matches!(session.remote_choice(round.0), Some(Err(_))) {
+        warn!(
+            "Player {} revealed a choice that doesn't match its commitment",
+            session.remote_player()
+        );
+    } else if session.reveal_overdue(round.0) {
+        warn!(
+            "Player {} didn't reveal its choice in time",
+            session.remote_player()
+        );
+    } else {
+        return;
+    }
+
+    let event = GameOverEvent {
+        player: Some(session.local_player()),
+        state: GameOvers::Winner,
+    };
+
+    game_over.0 = Some(event);
+
+    ev_alert.send(AlertEvent {
+        value: "Opponent forfeits!".into(),
+    });
+
+    ev_game_over.send(event);
+}
+
+fn despawn_waiting_text(mut commands: Commands, query: Query<Entity, With<WaitingText>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn leave_when_remote_left(
+    session: Res<NetSession>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if session.remote_left() {
+        warn!("Opponent left the game");
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(AppStates::Menu),
+        spawn_online_status_text.run_if(resource_exists::<NetSession>),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            exchange_status,
+            (
+                online_status_text_update,
+                host_starts_game.run_if(|session: Res<NetSession>| session.role == NetRoles::Host),
+            )
+                .run_if(in_state(AppStates::Menu)),
+            join_follows_host
+                .run_if(|session: Res<NetSession>| session.role == NetRoles::Join)
+                .run_if(in_state(AppStates::Menu).or(in_state(AppStates::Lobby))),
+            (
+                (
+                    lock_choice,
+                    reveal_choice,
+                    resolve_choices,
+                    forfeit_on_bad_reveal,
+                )
+                    .chain()
+                    .run_if(in_state(PlayStates::Betting)),
+                leave_when_remote_left.run_if(not(in_state(PlayStates::GameOver))),
+            )
+                .run_if(in_state(AppStates::InGame)),
+        )
+            .chain()
+            .run_if(resource_exists::<NetSession>),
+    );
+
+    app.add_systems(
+        OnExit(PlayStates::Betting),
+        despawn_waiting_text.run_if(resource_exists::<NetSession>),
+    );
+}
@@ -0,0 +1,108 @@
+// SHOWING A RESOLVED CORRESPONDENCE ROUND
+use super::*;
+
+fn start_resolving(mut next_app_state: ResMut<NextState<AppStates>>) {
+    next_app_state.set(AppStates::Watching);
+}
+
+type ResolvedStats<'a> = (
+    &'a mut Health,
+    &'a mut Bullets,
+    &'a mut Dodges,
+    &'a mut PlayerState,
+    &'a mut Buff,
+    &'a Player,
+);
+
+// The arena is the one of a watched match, set to the end of the new round
+fn show_resolved_round(
+    correspondence: Res<CorrespondenceMatch>,
+    mut query: Query<ResolvedStats>,
+    mut query_play_state: Query<&mut Text, (With<PlayStateText>, Without<RoundNumberText>)>,
+    mut query_round: Query<&mut Text, (With<RoundNumberText>, Without<PlayStateText>)>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut ev_alert: EventWriter<AlertEvent>,
+) {
+    let match_file = &correspondence.match_file;
+    let Some(last_round) = match_file.history.last() else {
+        return;
+    };
+    // Checked when the round was resolved
+    let Ok(duel) = match_file.replay() else {
+        return;
+    };
+
+    for (mut health, mut bullets, mut dodges, mut player_state, mut buff, player) in &mut query {
+        let index = (player.value - 1) as usize;
+        let fighter = &duel.fighters[index];
+
+        health.value = fighter.health;
+        health.max = fighter.max_health;
+        bullets.value = fighter.bullets;
+        dodges.value = fighter.dodges;
+        buff.value = last_round.buffs[index];
+        player_state.0 = last_round.states[index];
+
+        ev_change_player_state.send(PlayerStateChangeEvent {
+            player: player.value,
+        });
+
+        if let Some(buff) = last_round.buffs[index] {
+            ev_tick_player.send(TickPlayerEvent {
+                player: player.value,
+                value: format!("{:?}", buff),
+            });
+        }
+
+        if last_round.damage_taken[index] > 0 {
+            ev_tick_player.send(TickPlayerEvent {
+                player: player.value,
+                value: format!("-{} HP", last_round.damage_taken[index]),
+            });
+        }
+    }
+
+    for mut text in &mut query_round {
+        **text = format!("Round {}/{}", last_round.round, duel.rules.max_round);
+    }
+
+    for mut text in &mut query_play_state {
+        **text = "Correspondence".into();
+    }
+
+    ev_alert.send(AlertEvent {
+        value: match (match_file.finished, match_file.winner) {
+            (true, Some(winner)) => format!("Player {} wins!", winner),
+            (true, None) => "It's a tie!".into(),
+            (false, _) => format!("Round {} resolved", last_round.round),
+        },
+    });
+}
+
+fn forget_resolved_round(mut commands: Commands) {
+    commands.remove_resource::<CorrespondenceMatch>();
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        start_resolving
+            .run_if(in_state(AppStates::Menu))
+            .run_if(resource_exists::<CorrespondenceMatch>),
+    );
+
+    app.add_systems(
+        OnEnter(AppStates::Watching),
+        show_resolved_round
+            .after(watching::spawn_fighters)
+            .after(in_game::spawn_play_state_text)
+            .after(in_game::spawn_round_number_text)
+            .run_if(resource_exists::<CorrespondenceMatch>),
+    );
+
+    app.add_systems(
+        OnExit(AppStates::Watching),
+        forget_resolved_round.run_if(resource_exists::<CorrespondenceMatch>),
+    );
+}
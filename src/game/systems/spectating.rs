@@ -0,0 +1,121 @@
+// SPECTATOR STREAM
+use super::*;
+
+fn stream_play_state(play_state: Res<State<PlayStates>>, server: Res<SpectatorServer>) {
+    if play_state.is_changed() {
+        server.broadcast(&SpectatorMessage::PlayState {
+            state: *play_state.get(),
+        });
+    }
+}
+
+fn stream_fight_events(
+    mut ev_attack: EventReader<AttackEvent>,
+    mut ev_damage: EventReader<DamageEvent>,
+    mut ev_missed: EventReader<MissedEvent>,
+    mut ev_dodged: EventReader<DodgedEvent>,
+    server: Res<SpectatorServer>,
+) {
+    for ev in ev_attack.read() {
+        server.broadcast(&SpectatorMessage::Attack {
+            player: ev.player,
+            attacker: ev.attacker,
+            damage: ev.damage,
+        });
+    }
+
+    for ev in ev_damage.read() {
+        server.broadcast(&SpectatorMessage::Damage {
+            player: ev.player,
+            attacker: ev.attacker,
+            value: ev.value,
+        });
+    }
+
+    for ev in ev_missed.read() {
+        server.broadcast(&SpectatorMessage::Missed { player: ev.player });
+    }
+
+    for ev in ev_dodged.read() {
+        server.broadcast(&SpectatorMessage::Dodged { player: ev.player });
+    }
+}
+
+fn stream_game_overs(mut ev_game_over: EventReader<GameOverEvent>, server: Res<SpectatorServer>) {
+    for ev in ev_game_over.read() {
+        server.broadcast(&SpectatorMessage::GameOver { winner: ev.player });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn stream_snapshots(
+    time: Res<Time>,
+    mut server: ResMut<SpectatorServer>,
+    round: Res<RoundCounter>,
+    play_state: Res<State<PlayStates>>,
+    betting_timer: Res<BettingTimer>,
+    match_history: Res<MatchHistory>,
+    rule_set: Res<RuleSet>,
+    query: Query<(&Health, &Bullets, &Dodges, &PlayerState, &Buff, &Player)>,
+) {
+    server.snapshot_timer.tick(time.delta());
+
+    if !server.snapshot_timer.just_finished() && !play_state.is_changed() {
+        return;
+    }
+
+    // Choices stay hidden from the big screen until betting is over
+    let hidden = *play_state.get() == PlayStates::Betting;
+
+    let mut players: Vec<_> = query
+        .iter()
+        .map(
+            |(health, bullets, dodges, player_state, buff, player)| PlayerSnapshot {
+                player: player.value,
+                health: health.value,
+                max_health: health.max,
+                bullets: bullets.value,
+                dodges: dodges.value,
+                state: if hidden {
+                    PlayerStates::Idle
+                } else {
+                    player_state.0
+                },
+                buff: buff.value,
+            },
+        )
+        .collect();
+
+    players.sort_by_key(|snapshot| snapshot.player);
+
+    let betting_time_left = match play_state.get() {
+        PlayStates::Betting => betting_timer.0.remaining_secs(),
+        _ => 0.,
+    };
+
+    server.broadcast(&SpectatorMessage::Snapshot(MatchSnapshot {
+        round: round.0,
+        max_round: rule_set.max_round,
+        play_state: *play_state.get(),
+        betting_time_left,
+        players,
+        win_chances: match_history
+            .rounds
+            .last()
+            .and_then(|entry| entry.win_chances),
+    }));
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            stream_play_state,
+            stream_fight_events,
+            stream_game_overs,
+            stream_snapshots,
+        )
+            .run_if(in_state(AppStates::InGame))
+            .run_if(resource_exists::<SpectatorServer>),
+    );
+}
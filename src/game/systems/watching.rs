@@ -0,0 +1,236 @@
+// WATCHING A STREAMED MATCH
+use super::*;
+
+fn start_watching(mut next_app_state: ResMut<NextState<AppStates>>) {
+    next_app_state.set(AppStates::Watching);
+}
+
+// Both fighters with the default stats, until the stream says otherwise
+pub(super) fn spawn_fighters(
+    mut commands: Commands,
+    hand_texture: Res<assets::HandSpritesheet>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let Some(texture) = hand_texture.spritesheet.as_ref() else {
+        return;
+    };
+
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(150), 4, 1, None, None);
+    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+
+    for (player, x) in [(1, -350.), (2, 350.)] {
+        commands.spawn((
+            Player { value: player },
+            Target {
+                value: player % 2 + 1,
+            },
+            PlayerState(PlayerStates::Idle),
+            Sprite {
+                image: texture.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: texture_atlas_layout.clone(),
+                    index: 1,
+                }),
+                custom_size: Some(Vec2::new(500., 500.)),
+                flip_x: player == 2,
+                ..default()
+            },
+            Transform::from_xyz(x, 0.0, 0.0),
+            InGameEntity,
+        ));
+    }
+}
+
+fn spawn_watching_hint(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(DEFAULT_MARGIN / 2.),
+            left: Val::Px(DEFAULT_MARGIN / 2.),
+            ..default()
+        },
+        Text::new("Watching, Esc to leave"),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(Color::srgba(1., 1., 1., 0.5)),
+        GlobalZIndex(1),
+        InGameEntity,
+    ));
+}
+
+type WatchedStats<'a> = (
+    &'a mut Health,
+    &'a mut Bullets,
+    &'a mut Dodges,
+    &'a mut PlayerState,
+    &'a mut Buff,
+    &'a Player,
+);
+
+fn apply_snapshot(
+    snapshot: &MatchSnapshot,
+    query: &mut Query<WatchedStats>,
+    ev_change_player_state: &mut EventWriter<PlayerStateChangeEvent>,
+    ev_tick_player: &mut EventWriter<TickPlayerEvent>,
+) {
+    for (mut health, mut bullets, mut dodges, mut player_state, mut buff, player) in query {
+        let Some(fighter) = snapshot
+            .players
+            .iter()
+            .find(|fighter| fighter.player == player.value)
+        else {
+            continue;
+        };
+
+        // Hits only show up as health going down
+        if fighter.health < health.value {
+            ev_tick_player.send(TickPlayerEvent {
+                player: player.value,
+                value: format!("-{} HP", health.value - fighter.health),
+            });
+        }
+
+        if player_state.0 != fighter.state {
+            player_state.0 = fighter.state;
+            ev_change_player_state.send(PlayerStateChangeEvent {
+                player: player.value,
+            });
+        }
+
+        health.value = fighter.health;
+        health.max = fighter.max_health;
+        bullets.value = fighter.bullets;
+        dodges.value = fighter.dodges;
+        buff.value = fighter.buff;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_stream(
+    client: Res<SpectatorClient>,
+    mut query: Query<WatchedStats>,
+    mut query_play_state: Query<&mut Text, (With<PlayStateText>, Without<RoundNumberText>)>,
+    mut query_round: Query<&mut Text, (With<RoundNumberText>, Without<PlayStateText>)>,
+    mut ev_change_player_state: EventWriter<PlayerStateChangeEvent>,
+    mut ev_tick_player: EventWriter<TickPlayerEvent>,
+    mut ev_alert: EventWriter<AlertEvent>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut commands: Commands,
+) {
+    let Some(messages) = client.receive() else {
+        warn!("The stream on {} closed", client.address);
+        commands.remove_resource::<SpectatorClient>();
+        next_app_state.set(AppStates::Menu);
+        return;
+    };
+
+    for message in messages {
+        match message {
+            SpectatorMessage::Snapshot(snapshot) => {
+                apply_snapshot(
+                    &snapshot,
+                    &mut query,
+                    &mut ev_change_player_state,
+                    &mut ev_tick_player,
+                );
+
+                for mut text in &mut query_round {
+                    **text = format!("Round {}/{}", snapshot.round, snapshot.max_round);
+                }
+
+                for mut text in &mut query_play_state {
+                    **text = format!("{:?}", snapshot.play_state);
+                }
+            }
+            SpectatorMessage::PlayState { state } => {
+                for mut text in &mut query_play_state {
+                    **text = format!("{:?}", state);
+                }
+            }
+            SpectatorMessage::Missed { player } => {
+                ev_tick_player.send(TickPlayerEvent {
+                    player,
+                    value: "Missed!".into(),
+                });
+            }
+            SpectatorMessage::Dodged { player } => {
+                ev_tick_player.send(TickPlayerEvent {
+                    player,
+                    value: "Dodged!".into(),
+                });
+            }
+            SpectatorMessage::GameOver { winner } => {
+                ev_alert.send(AlertEvent {
+                    value: match winner {
+                        Some(winner) => format!("Player {} wins!", winner),
+                        None => "It's a tie!".into(),
+                    },
+                });
+            }
+            SpectatorMessage::Attack { .. } | SpectatorMessage::Damage { .. } => {}
+        }
+    }
+}
+
+// Ticks and alerts are despawned by the phases of a local match
+fn despawn_faded_ticks(
+    mut commands: Commands,
+    query: Query<(Entity, &TextColor), With<PlayerTickText>>,
+) {
+    for (entity, color) in &query {
+        if color.0.alpha() <= 0. {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn leave_watching(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut commands: Commands,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<SpectatorClient>();
+        next_app_state.set(AppStates::Menu);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        start_watching
+            .run_if(in_state(AppStates::Menu))
+            .run_if(resource_exists::<SpectatorClient>),
+    );
+
+    app.add_systems(
+        OnEnter(AppStates::Watching),
+        (
+            in_game::spawn_bg,
+            spawn_fighters,
+            (
+                in_game::spawn_play_state_text,
+                in_game::spawn_round_number_text,
+                in_game::spawn_health_bar,
+                in_game::spawn_mana_bar,
+                in_game::spawn_stamina_bar,
+                spawn_watching_hint.run_if(not(resource_exists::<ServerConnection>)),
+            ),
+        )
+            .chain(),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            follow_stream.run_if(resource_exists::<SpectatorClient>),
+            despawn_faded_ticks,
+            leave_watching,
+        )
+            .run_if(in_state(AppStates::Watching)),
+    );
+
+    app.add_systems(OnExit(AppStates::Watching), clean_system::<InGameEntity>);
+}
@@ -0,0 +1,150 @@
+// Human readable account of a match for analysis, written as JSON and as
+// CSV (one row per player and round) once the match is over.
+use std::{
+    fmt::Debug,
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::*;
+
+const CSV_HEADER: &str = "round,player,modifier,choice,state,buff,\
+health_before,bullets_before,dodges_before,health_after,bullets_after,dodges_after,\
+shot_outcome,marksmanship_roll,luck_roll,damage,depleted,result";
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct TimelineResources {
+    pub health: u8,
+    pub bullets: u8,
+    pub dodges: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ShotOutcomes {
+    Hit,
+    Missed,
+    Dodged,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TimelineShot {
+    pub attacker: u8,
+    pub target: u8,
+    pub damage: u8,
+    // Dodged shots roll no dice
+    pub roll: Option<HitRoll>,
+    pub outcome: ShotOutcomes,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelinePlayer {
+    pub player: u8,
+    pub choice: Moves,
+    // What was played once buffs forced their moves
+    pub state: Option<PlayerStates>,
+    pub buff: Option<Buffes>,
+    pub depleted: Vec<DepletedSources>,
+    pub before: TimelineResources,
+    // After restores, when the round went that far
+    pub after: Option<TimelineResources>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineRound {
+    pub round: u8,
+    pub modifier: Option<RoundModifiers>,
+    pub players: [TimelinePlayer; 2],
+    pub shots: Vec<TimelineShot>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TimelineResult {
+    pub result: GameOvers,
+    pub winner: Option<u8>,
+}
+
+#[derive(Resource, Debug, Default, Serialize)]
+pub struct MatchTimeline {
+    pub rounds: Vec<TimelineRound>,
+    pub result: Option<TimelineResult>,
+}
+
+fn label(value: Option<impl Debug>) -> String {
+    value.map_or(String::new(), |value| format!("{:?}", value))
+}
+
+impl MatchTimeline {
+    pub fn directory() -> PathBuf {
+        PathBuf::from(SAVE_DIR).join("timelines")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        let last_round = self.rounds.last().map(|round| round.round);
+
+        for round in &self.rounds {
+            for player in &round.players {
+                let before = player.before;
+                let after = player.after;
+                let shot = round.shots.iter().find(|shot| shot.target == player.player);
+                let depleted = player
+                    .depleted
+                    .iter()
+                    .map(|source| format!("{:?}", source))
+                    .collect::<Vec<_>>()
+                    .join("+");
+                let result = match self.result {
+                    Some(result) if Some(round.round) == last_round => match result.winner {
+                        Some(winner) => format!("Winner {}", winner),
+                        None => format!("{:?}", result.result),
+                    },
+                    _ => String::new(),
+                };
+
+                csv += &[
+                    round.round.to_string(),
+                    player.player.to_string(),
+                    label(round.modifier),
+                    format!("{:?}", player.choice),
+                    label(player.state),
+                    label(player.buff),
+                    before.health.to_string(),
+                    before.bullets.to_string(),
+                    before.dodges.to_string(),
+                    after.map_or(String::new(), |after| after.health.to_string()),
+                    after.map_or(String::new(), |after| after.bullets.to_string()),
+                    after.map_or(String::new(), |after| after.dodges.to_string()),
+                    label(shot.map(|shot| shot.outcome)),
+                    label(
+                        shot.and_then(|shot| shot.roll)
+                            .map(|roll| roll.marksmanship),
+                    ),
+                    label(shot.and_then(|shot| shot.roll).map(|roll| roll.luck)),
+                    label(shot.map(|shot| shot.damage)),
+                    depleted,
+                    result,
+                ]
+                .join(",");
+                csv += "\n";
+            }
+        }
+
+        csv
+    }
+
+    // Both files share their name, the JSON one is returned
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let directory = Self::directory();
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(io::Error::other)?;
+        let path = directory.join(format!("timeline-{}.json", recorded_at.as_millis()));
+
+        fs::create_dir_all(&directory)?;
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        fs::write(path.with_extension("csv"), self.to_csv())?;
+
+        Ok(path)
+    }
+}
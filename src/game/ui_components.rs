@@ -14,11 +14,15 @@ pub struct HealthBar {
 #[derive(Component)]
 pub struct ManaBar {
     pub value: u8,
+    // Most bullets the player has held, bullets have no maximum
+    pub full: u8,
 }
 
 #[derive(Component)]
 pub struct StaminaBar {
     pub value: u8,
+    // Most dodges the player has held, dodges have no maximum
+    pub full: u8,
 }
 
 #[derive(Component)]
@@ -37,3 +41,54 @@ pub struct PlayerTickText;
 
 #[derive(Component)]
 pub struct AlertText;
+
+#[derive(Component)]
+pub struct MatchSettingsText;
+
+#[derive(Component)]
+pub struct DraftText {
+    pub value: u8,
+}
+
+#[derive(Component)]
+pub struct HandicapText;
+
+#[derive(Component)]
+pub struct ScenarioText;
+
+#[derive(Component)]
+pub struct DifficultyText;
+
+#[derive(Component)]
+pub struct WinMeterSegment {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct WinMeterText;
+
+#[derive(Component)]
+pub struct DemoText;
+
+#[derive(Component)]
+pub struct ReplayText;
+
+#[derive(Component)]
+pub struct OnlineStatusText;
+
+#[derive(Component)]
+pub struct WaitingText;
+
+#[derive(Component)]
+pub struct LobbyText;
+
+#[derive(Component)]
+pub struct AudienceText;
+
+#[derive(Component)]
+pub struct ServerStatusText;
+
+#[derive(Component)]
+pub struct LockedText {
+    pub value: u8,
+}
@@ -1,8 +1,15 @@
-use bevy::{asset::load_internal_binary_asset, prelude::*, window::WindowResolution};
+use std::time::Duration;
+
+use bevy::{
+    app::ScheduleRunnerPlugin, asset::load_internal_binary_asset, log::LogPlugin, prelude::*,
+    window::WindowResolution,
+};
 
 mod camera;
 mod game;
 
+pub use game::headless;
+
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
@@ -35,3 +42,20 @@ impl Plugin for AppPlugin {
         // #[cfg(feature = "dev")]
     }
 }
+
+// Window-free app resolving networked duels, see `bin/server.rs`
+pub struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            // 60 ticks a second, the betting timer needs no more
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+                1. / 60.,
+            ))),
+            LogPlugin::default(),
+        ));
+
+        app.add_plugins(game::server_plugin);
+    }
+}